@@ -0,0 +1,43 @@
+//! Compares `Trie::from_sorted` with inserting the same words one by one.
+//!
+//! Run with `cargo bench --bench from_sorted`.
+
+use std::{hint::black_box, time::Instant};
+
+use trie::Trie;
+
+// Every word of length 1 to 5 over a small alphabet, in sorted order
+fn dictionary() -> Vec<String> {
+    let mut words = vec![String::new()];
+    let mut all = Vec::new();
+    for _ in 0..5 {
+        words = words
+            .iter()
+            .flat_map(|word| ('a'..='j').map(move |letter| format!("{word}{letter}")))
+            .collect();
+        all.extend(words.iter().cloned());
+    }
+    all.sort();
+    all
+}
+
+fn time(label: &str, runs: u32, mut f: impl FnMut() -> Trie) {
+    let start = Instant::now();
+    for _ in 0..runs {
+        black_box(f());
+    }
+    println!("{label:<12} {:?} per build", start.elapsed() / runs);
+}
+
+fn main() {
+    let words = dictionary();
+    println!("{} words", words.len());
+    time("insert loop", 10, || {
+        let mut trie = Trie::new();
+        for word in &words {
+            trie.insert(word);
+        }
+        trie
+    });
+    time("from_sorted", 10, || Trie::from_sorted(&words));
+}
@@ -0,0 +1,60 @@
+//! Times inserting and looking up a large ASCII word list, the common case
+//! the normalization fast path is for.
+//!
+//! Run with `cargo bench --bench insert`.
+
+use std::{hint::black_box, time::Instant};
+
+use trie::Trie;
+
+// Pseudo-random mixed-case ASCII words of 3 to 12 letters
+fn dictionary() -> Vec<String> {
+    let mut state = 0x2545_f491_u32;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        state
+    };
+    (0..200_000)
+        .map(|_| {
+            let len = 3 + next() % 10;
+            (0..len)
+                .map(|i| {
+                    let letter = char::from(b'a' + (next() % 26) as u8);
+                    if i == 0 && next() % 4 == 0 {
+                        letter.to_ascii_uppercase()
+                    } else {
+                        letter
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn time(label: &str, runs: u32, mut f: impl FnMut()) {
+    let start = Instant::now();
+    for _ in 0..runs {
+        f();
+    }
+    println!("{label:<10} {:?} per run", start.elapsed() / runs);
+}
+
+fn main() {
+    let words = dictionary();
+    println!("{} words", words.len());
+    time("insert", 5, || {
+        let mut trie = Trie::new();
+        for word in &words {
+            trie.insert(word);
+        }
+        black_box(trie);
+    });
+    let trie: Trie = words.iter().collect();
+    time("contains", 5, || {
+        for word in &words {
+            black_box(trie.contains(word));
+        }
+    });
+}
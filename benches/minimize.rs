@@ -0,0 +1,49 @@
+//! Compares the node counts of a `Trie` and its `MinimizedTrie` on an
+//! inflected word list, and times the minimization.
+//!
+//! Run with `cargo bench --bench minimize`.
+
+use std::{hint::black_box, time::Instant};
+
+use trie::Trie;
+
+// Made-up stems, each taking its own mix of English endings, to mimic the
+// shared suffixes of a real dictionary
+fn dictionary() -> Vec<String> {
+    let endings = ["", "s", "ed", "ing", "er", "ers", "able", "ment", "ments"];
+    let mut words = Vec::new();
+    let mut stem = 0_usize;
+    for first in 'b'..='z' {
+        for vowel in ['a', 'e', 'i', 'o', 'u'] {
+            for last in ['k', 'l', 'm', 'n', 'p', 'r', 's', 't'] {
+                stem += 1;
+                let mix = stem.wrapping_mul(2_654_435_761) >> 7;
+                for (i, ending) in endings.iter().enumerate() {
+                    if i == 0 || mix & (1 << i) != 0 {
+                        words.push(format!("{first}{vowel}{last}{last}{ending}"));
+                    }
+                }
+            }
+        }
+    }
+    words
+}
+
+fn main() {
+    let words = dictionary();
+    let trie: Trie = words.iter().collect();
+    println!("{} words", trie.len());
+    let runs = 10;
+    let start = Instant::now();
+    for _ in 0..runs {
+        black_box(trie.minimize());
+    }
+    let elapsed = start.elapsed() / runs;
+    let minimized = trie.minimize();
+    println!("trie         {} nodes", trie.node_count());
+    println!(
+        "minimized    {} nodes ({:.1}%), built in {elapsed:?}",
+        minimized.node_count(),
+        100.0 * minimized.node_count() as f64 / trie.node_count() as f64
+    );
+}
@@ -0,0 +1,108 @@
+use alloc::{collections::BTreeMap, string::String, vec, vec::Vec};
+
+use crate::Trie;
+
+impl<V> Trie<V> {
+    /// Returns every stored word that uses exactly the letters in `letters`,
+    /// in alphabetical order.
+    ///
+    /// `letters` is a multiset: `"aet"` finds `"ate"`, `"eat"` and `"tea"`,
+    /// but not `"teat"`. It is normalized like any other input.
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let trie: Trie = ["eat", "tea", "tan", "ate"].iter().collect();
+    /// assert_eq!(trie.anagrams("aet"), vec!["ate", "eat", "tea"]);
+    /// ```
+    pub fn anagrams(&self, letters: &str) -> Vec<String> {
+        self.anagrams_with(letters, false)
+    }
+
+    /// Like [`anagrams`](Self::anagrams), but with `subset` set also returns
+    /// words that leave some of the letters unused, so `"aet"` also finds
+    /// `"at"` and `"a"`.
+    pub fn anagrams_with(&self, letters: &str, subset: bool) -> Vec<String> {
+        let Some(keys) = self.normalize(letters) else {
+            return Vec::new();
+        };
+        let mut remaining = BTreeMap::new();
+        for char_code in &keys {
+            *remaining.entry(*char_code).or_insert(0) += 1;
+        }
+        // `left` is the total of `remaining`, so exact matches are spotted
+        // without summing the table at every node
+        let mut left = keys.len();
+        let mut path = String::new();
+        let mut matches = Vec::new();
+        // Nodes still to visit, each with the depth of its parent and the
+        // letter leading to it. Going back up to a shallower node hands the
+        // letters below it back. The explicit stack keeps a long word
+        // spelled by a long `letters` from recursing.
+        let mut stack = vec![(&self.root, 0, None)];
+        while let Some((node, parent_depth, char_code)) = stack.pop() {
+            while keys.len() - left > parent_depth {
+                let char_code = path.pop().unwrap();
+                *remaining.get_mut(&char_code).unwrap() += 1;
+                left += 1;
+            }
+            if let Some(char_code) = char_code {
+                *remaining.get_mut(&char_code).unwrap() -= 1;
+                left -= 1;
+                path.push(char_code);
+            }
+            if node.is_end_of_word() && (subset || left == 0) {
+                matches.push(node.display_word(path.clone()));
+            }
+            // Only letters still left can lead on, and pushing in reverse
+            // visits the smallest first
+            let depth = keys.len() - left;
+            for (char_code, child) in node.children.iter().rev() {
+                if remaining.get(char_code).is_some_and(|count| *count > 0) {
+                    stack.push((child, depth, Some(*char_code)));
+                }
+            }
+        }
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Trie;
+
+    #[test]
+    fn test_anagrams_exact() {
+        let trie: Trie = ["eat", "tea", "tan", "ate", "at", "teat"].iter().collect();
+        assert_eq!(trie.anagrams("aet"), vec!["ate", "eat", "tea"]);
+        assert_eq!(trie.anagrams("T-E-A"), vec!["ate", "eat", "tea"]);
+        assert_eq!(trie.anagrams("ttea"), vec!["teat"]);
+        assert!(trie.anagrams("xyz").is_empty());
+        assert!(trie.anagrams("").is_empty());
+    }
+
+    #[test]
+    fn test_anagrams_subset() {
+        let trie: Trie = ["eat", "tea", "tan", "ate", "at", "a", "teat"]
+            .iter()
+            .collect();
+        assert_eq!(
+            trie.anagrams_with("aet", true),
+            vec!["a", "at", "ate", "eat", "tea"]
+        );
+        assert_eq!(trie.anagrams_with("aet", false), trie.anagrams("aet"));
+        assert!(trie.anagrams_with("", true).is_empty());
+    }
+
+    #[test]
+    fn test_anagrams_very_long_word() {
+        // Deep enough to overflow the stack with a recursive search
+        let word = "ab".repeat(25_000);
+        let trie: Trie = [word.as_str(), "ba", "ab"].iter().collect();
+        assert_eq!(trie.anagrams(&"ba".repeat(25_000)), vec![word.clone()]);
+        assert_eq!(
+            trie.anagrams_with(&word, true),
+            vec![String::from("ab"), word.clone(), String::from("ba")]
+        );
+    }
+}
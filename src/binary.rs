@@ -0,0 +1,339 @@
+//! A compact binary format for word lists, enabled by the `std` feature.
+
+use std::io::{self, BufWriter, ErrorKind, Read, Write};
+
+use alloc::{vec, vec::Vec};
+
+use crate::{Trie, TrieNode};
+
+const MAGIC: &[u8; 4] = b"TRIE";
+const VERSION: u8 = 1;
+const END_OF_WORD: u8 = 1;
+
+impl Trie {
+    /// Writes the words, and how often each was inserted, to `w` in a
+    /// compact binary format.
+    ///
+    /// The output starts with the magic bytes `b"TRIE"` and a version byte,
+    /// currently 1, so other input and future versions are detected. The
+    /// nodes follow in preorder, children in alphabetical order, one record
+    /// each:
+    ///
+    /// - the letter leading to the node, in UTF-8 (absent for the root),
+    /// - a flags byte, with bit 0 set if a word ends at the node,
+    /// - the number of children, as an unsigned LEB128 varint,
+    /// - for a word, the number of times it was inserted, as a varint.
+    ///
+    /// Shared prefixes are written once, so an ASCII word list takes about
+    /// three bytes per node, and loading rebuilds the nodes directly instead
+    /// of inserting every word again.
+    ///
+    /// The configuration isn't saved: like the serde format, this stores
+    /// words, and a load gives them back in a trie configured like
+    /// [`Trie::new`]. Case-preserved spellings aren't saved either. A trie
+    /// with letters a default one never stores, such as the uppercase keys
+    /// of a case-sensitive trie, couldn't be loaded back, so saving it gives
+    /// an [`ErrorKind::InvalidInput`] error before anything is written.
+    /// Writes are buffered internally.
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let trie: Trie = ["ape", "apple"].iter().collect();
+    /// let mut bytes = Vec::new();
+    /// trie.save_binary(&mut bytes)?;
+    /// assert_eq!(Trie::load_binary(&mut bytes.as_slice())?, trie);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn save_binary(&self, w: &mut impl Write) -> io::Result<()> {
+        let config = Trie::new();
+        let mut keys = Vec::new();
+        let mut stack = vec![&self.root];
+        while let Some(node) = stack.pop() {
+            for (char_code, child) in node.children.iter() {
+                if !is_default_key(&config, *char_code, &mut keys) {
+                    return Err(io::Error::new(
+                        ErrorKind::InvalidInput,
+                        "a letter a default trie doesn't store",
+                    ));
+                }
+                stack.push(child);
+            }
+        }
+        let mut w = BufWriter::new(w);
+        w.write_all(MAGIC)?;
+        w.write_all(&[VERSION])?;
+        let mut stack = vec![(&self.root, None::<char>)];
+        while let Some((node, char_code)) = stack.pop() {
+            if let Some(char_code) = char_code {
+                let mut utf8 = [0; 4];
+                w.write_all(char_code.encode_utf8(&mut utf8).as_bytes())?;
+            }
+            let flags = if node.is_end_of_word() {
+                END_OF_WORD
+            } else {
+                0
+            };
+            w.write_all(&[flags])?;
+            write_varint(&mut w, node.children.len() as u64)?;
+            if node.is_end_of_word() {
+                write_varint(&mut w, node.count.into())?;
+            }
+            stack.extend(
+                node.children
+                    .iter()
+                    .rev()
+                    .map(|(char_code, child)| (child, Some(*char_code))),
+            );
+        }
+        w.flush()
+    }
+
+    /// Reads a trie written by [`save_binary`](Self::save_binary).
+    ///
+    /// Reads are small, so pass a `BufReader` rather than a bare `File`.
+    /// Nothing past the end of the trie is read. Input that doesn't start
+    /// with the magic bytes, has an unknown version or isn't a valid trie
+    /// gives an [`ErrorKind::InvalidData`] error. So do letters that
+    /// [`Trie::new`] would never store, which its lookups couldn't reach.
+    pub fn load_binary(r: &mut impl Read) -> io::Result<Trie> {
+        let mut header = [0; 5];
+        r.read_exact(&mut header)?;
+        if &header[..4] != MAGIC {
+            return Err(invalid("not a binary trie"));
+        }
+        if header[4] != VERSION {
+            return Err(invalid("unsupported binary trie version"));
+        }
+        // The configuration the loaded trie gets, which every letter must fit
+        let config = Trie::new();
+        let mut keys = Vec::new();
+        let mut root = TrieNode::new();
+        if read_byte(r)? != 0 {
+            return Err(invalid("the root can't be a word"));
+        }
+        // The nodes still being read, each with how many of its children are
+        // left and the letter of the last one read, root first
+        let mut open: Vec<(char, TrieNode<()>)> = Vec::new();
+        let mut frames = vec![(read_varint(r)?, None)];
+        // Insertion order isn't stored, so words are numbered as they're read
+        let mut next_seq = 0;
+        loop {
+            let (left, last) = frames.last_mut().unwrap();
+            if *left == 0 {
+                frames.pop();
+                if frames.is_empty() {
+                    break;
+                }
+                Trie::close_path(&mut root, &mut open, frames.len() - 1);
+                continue;
+            }
+            *left -= 1;
+            let char_code = read_char(r)?;
+            if last.is_some_and(|last| last >= char_code) {
+                return Err(invalid("children out of order"));
+            }
+            *last = Some(char_code);
+            if !is_default_key(&config, char_code, &mut keys) {
+                return Err(invalid("a letter outside the alphabet"));
+            }
+            let flags = read_byte(r)?;
+            if flags & !END_OF_WORD != 0 {
+                return Err(invalid("unknown node flags"));
+            }
+            let children = read_varint(r)?;
+            let mut node = TrieNode::new();
+            if flags & END_OF_WORD != 0 {
+                let count = u32::try_from(read_varint(r)?)
+                    .ok()
+                    .filter(|count| *count > 0)
+                    .ok_or_else(|| invalid("invalid insert count"))?;
+                node.value = Some(());
+                node.count = count;
+                node.seq = next_seq;
+                next_seq += 1;
+                node.word_count = 1;
+                node.passes = count;
+            } else if children == 0 {
+                return Err(invalid("a branch without words"));
+            }
+            open.push((char_code, node));
+            frames.push((children, None));
+        }
+        Ok(Trie {
+            len: root.word_count,
+            next_seq,
+            root,
+            ..config
+        })
+    }
+}
+
+// Whether a trie configured like `Trie::new` stores `char_code` as a key of
+// its own, which are the only letters the format allows. `keys` is scratch
+// space.
+fn is_default_key(config: &Trie, char_code: char, keys: &mut Vec<char>) -> bool {
+    keys.clear();
+    config.push_keys(char_code, keys) && *keys == [char_code]
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, message)
+}
+
+fn write_varint(w: &mut impl Write, mut n: u64) -> io::Result<()> {
+    while n >= 0x80 {
+        w.write_all(&[n as u8 | 0x80])?;
+        n >>= 7;
+    }
+    w.write_all(&[n as u8])
+}
+
+fn read_byte(r: &mut impl Read) -> io::Result<u8> {
+    let mut byte = [0];
+    r.read_exact(&mut byte)?;
+    Ok(byte[0])
+}
+
+fn read_varint(r: &mut impl Read) -> io::Result<u64> {
+    let mut n = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = read_byte(r)?;
+        n |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(n);
+        }
+    }
+    Err(invalid("varint too long"))
+}
+
+fn read_char(r: &mut impl Read) -> io::Result<char> {
+    let mut utf8 = [0; 4];
+    utf8[0] = read_byte(r)?;
+    let len = match utf8[0] {
+        0x00..0x80 => 1,
+        0xc0..0xe0 => 2,
+        0xe0..0xf0 => 3,
+        0xf0..0xf8 => 4,
+        _ => return Err(invalid("invalid UTF-8")),
+    };
+    r.read_exact(&mut utf8[1..len])?;
+    let text = core::str::from_utf8(&utf8[..len]).map_err(|_| invalid("invalid UTF-8"))?;
+    Ok(text.chars().next().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::ErrorKind;
+
+    use crate::Trie;
+
+    fn save(trie: &Trie) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        trie.save_binary(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut trie: Trie = ["apple", "ape", "app", "ball", "café", "日本", "a"]
+            .iter()
+            .collect();
+        trie.insert("apple");
+        let bytes = save(&trie);
+        assert_eq!(Trie::load_binary(&mut bytes.as_slice()).unwrap(), trie);
+        let long = "z".repeat(10_000);
+        trie.insert(&long);
+        let bytes = save(&trie);
+        let loaded = Trie::load_binary(&mut bytes.as_slice()).unwrap();
+        assert_eq!(loaded.len(), trie.len());
+        assert_eq!(loaded.words(""), trie.words(""));
+        assert_eq!(loaded.most_common("", 2), trie.most_common("", 2));
+        assert_eq!(loaded.prefix_frequency("ap"), 4);
+        assert_eq!(loaded.count_words_with_prefix("a"), 4);
+        assert!(loaded.contains(&long));
+        assert!(bytes.len() < 4 * trie.node_count());
+    }
+
+    #[test]
+    fn test_round_trip_empty() {
+        let bytes = save(&Trie::new());
+        assert_eq!(bytes, b"TRIE\x01\x00\x00");
+        assert!(Trie::load_binary(&mut bytes.as_slice()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_stops_at_end_of_trie() {
+        let trie: Trie = ["one", "two"].iter().collect();
+        let mut bytes = save(&trie);
+        bytes.extend_from_slice(b"rest");
+        let mut input = bytes.as_slice();
+        assert_eq!(Trie::load_binary(&mut input).unwrap(), trie);
+        assert_eq!(input, b"rest");
+    }
+
+    #[test]
+    fn test_rejects_bad_input() {
+        let load = |bytes: &[u8]| Trie::load_binary(&mut &bytes[..]).unwrap_err().kind();
+        assert_eq!(load(b"JSON\x01\x00\x00"), ErrorKind::InvalidData);
+        assert_eq!(load(b"TRIE\x02\x00\x00"), ErrorKind::InvalidData);
+        assert_eq!(load(b"TRIE\x01\x01\x00"), ErrorKind::InvalidData);
+        // A leaf that isn't a word
+        assert_eq!(load(b"TRIE\x01\x00\x01a\x00\x00"), ErrorKind::InvalidData);
+        // Siblings out of order, or repeated
+        assert_eq!(
+            load(b"TRIE\x01\x00\x02b\x01\x00\x01a\x01\x00\x01"),
+            ErrorKind::InvalidData
+        );
+        assert_eq!(
+            load(b"TRIE\x01\x00\x02a\x01\x00\x01a\x01\x00\x01"),
+            ErrorKind::InvalidData
+        );
+        // A word inserted zero times
+        assert_eq!(
+            load(b"TRIE\x01\x00\x01a\x01\x00\x00"),
+            ErrorKind::InvalidData
+        );
+        assert_eq!(
+            load(b"TRIE\x01\x00\x01\xff\x01\x00\x01"),
+            ErrorKind::InvalidData
+        );
+        assert_eq!(
+            load(b"TRIE\x01\x00\x02a\x01\x00\x01"),
+            ErrorKind::UnexpectedEof
+        );
+        assert_eq!(load(b"TRI"), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_letters_outside_default_config() {
+        let load = |bytes: &[u8]| Trie::load_binary(&mut &bytes[..]).unwrap_err().kind();
+        let mut trie = Trie::builder().case_sensitive(true).build();
+        trie.insert("Nasa");
+        let mut bytes = Vec::new();
+        let error = trie.save_binary(&mut bytes).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert!(bytes.is_empty());
+        assert_eq!(
+            load(b"TRIE\x01\x00\x01N\x01\x00\x01"),
+            ErrorKind::InvalidData
+        );
+        // Neither punctuation nor whitespace is ever a key
+        assert_eq!(
+            load(b"TRIE\x01\x00\x01-\x01\x00\x01"),
+            ErrorKind::InvalidData
+        );
+        assert_eq!(
+            load(b"TRIE\x01\x00\x01 \x01\x00\x01"),
+            ErrorKind::InvalidData
+        );
+        trie.insert("nasa");
+        trie.remove("Nasa");
+        let bytes = save(&trie);
+        assert!(
+            Trie::load_binary(&mut bytes.as_slice())
+                .unwrap()
+                .contains("NASA")
+        );
+    }
+}
@@ -0,0 +1,208 @@
+use crate::{Alphabet, LengthPolicy, NonAlphaPolicy, Trie};
+
+/// Configures a [`Trie`] before creating it.
+///
+/// Every option defaults to the behavior of [`Trie::new`], so only the ones
+/// that differ need to be set:
+///
+/// ```
+/// use trie::{Alphabet, NonAlphaPolicy, Trie};
+///
+/// let mut trie = Trie::builder()
+///     .alphabet(Alphabet::AsciiLowercase)
+///     .non_alpha(NonAlphaPolicy::Reject)
+///     .build();
+/// trie.insert("apple");
+/// trie.insert("café");
+/// assert_eq!(trie.words(""), vec!["apple"]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TrieBuilder {
+    preserve_case: bool,
+    case_sensitive: bool,
+    alphabet: Alphabet,
+    #[cfg(feature = "fold-diacritics")]
+    fold_diacritics: bool,
+    non_alpha_policy: NonAlphaPolicy,
+    max_word_length: Option<usize>,
+    length_policy: LengthPolicy,
+}
+
+impl TrieBuilder {
+    /// Creates a builder with the default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stores words exactly as given instead of lowercasing them, so
+    /// `"Apple"` and `"apple"` become two different words. Off by default.
+    pub fn case_sensitive(mut self, case_sensitive: bool) -> Self {
+        self.case_sensitive = case_sensitive;
+        self
+    }
+
+    /// Reports words with the spelling they were inserted with, while still
+    /// matching them case-insensitively. See [`Trie::preserving_case`]. Off
+    /// by default.
+    pub fn preserve_case(mut self, preserve_case: bool) -> Self {
+        self.preserve_case = preserve_case;
+        self
+    }
+
+    /// Sets which characters are stored. [`Alphabet::Unicode`] by default.
+    pub fn alphabet(mut self, alphabet: Alphabet) -> Self {
+        self.alphabet = alphabet;
+        self
+    }
+
+    /// Strips accents and other combining marks, so `"café"`, `"cafe"` and
+    /// `"CAFÉ"` are all stored as `"cafe"`. Off by default, which matches
+    /// accented letters exactly. Enabled by the `fold-diacritics` feature.
+    ///
+    /// Words are decomposed (NFD), their combining marks dropped and the rest
+    /// recomposed (NFC), so letters without a decomposition, like `ø` or
+    /// `ł`, stay as they are.
+    #[cfg(feature = "fold-diacritics")]
+    pub fn fold_diacritics(mut self, fold_diacritics: bool) -> Self {
+        self.fold_diacritics = fold_diacritics;
+        self
+    }
+
+    /// Sets what happens to characters outside the alphabet.
+    /// [`NonAlphaPolicy::Skip`] by default.
+    pub fn non_alpha(mut self, policy: NonAlphaPolicy) -> Self {
+        self.non_alpha_policy = policy;
+        self
+    }
+
+    /// Sets the longest word accepted, in characters after normalization.
+    /// Unlimited by default. See [`Trie::with_max_word_length`].
+    pub fn max_word_length(mut self, max: usize) -> Self {
+        self.max_word_length = Some(max);
+        self
+    }
+
+    /// Sets what happens to words over the length limit.
+    /// [`LengthPolicy::Reject`] by default.
+    pub fn length_policy(mut self, policy: LengthPolicy) -> Self {
+        self.length_policy = policy;
+        self
+    }
+
+    /// Creates an empty set of words with this configuration.
+    pub fn build(self) -> Trie {
+        self.build_map()
+    }
+
+    /// Creates an empty `Trie<V>`, mapping words to values, with this
+    /// configuration.
+    pub fn build_map<V>(self) -> Trie<V> {
+        Trie {
+            preserve_case: self.preserve_case,
+            case_sensitive: self.case_sensitive,
+            alphabet: self.alphabet,
+            #[cfg(feature = "fold-diacritics")]
+            fold_diacritics: self.fold_diacritics,
+            non_alpha_policy: self.non_alpha_policy,
+            max_word_length: self.max_word_length,
+            length_policy: self.length_policy,
+            ..Trie::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Alphabet, NonAlphaPolicy, Trie, TrieError};
+
+    #[test]
+    fn test_default_build_matches_new() {
+        let mut built = Trie::builder().build();
+        let mut plain = Trie::new();
+        for word in ["Apple", "ape'", "café", "x1"] {
+            assert_eq!(built.insert(word), plain.insert(word));
+        }
+        assert_eq!(built, plain);
+        assert_eq!(built.words(""), plain.words(""));
+        assert_eq!(built.alphabet(), Alphabet::Unicode);
+        assert!(!built.is_case_sensitive());
+        assert_eq!(built.non_alpha_policy(), NonAlphaPolicy::Skip);
+    }
+
+    #[test]
+    fn test_case_sensitive() {
+        let mut trie = Trie::builder().case_sensitive(true).build();
+        assert!(trie.insert("Apple"));
+        assert!(trie.insert("apple"));
+        assert_eq!(trie.len(), 2);
+        assert!(trie.contains("Apple"));
+        assert!(!trie.contains("APPLE"));
+        assert_eq!(trie.words("A"), vec!["Apple"]);
+        // Uppercase sorts before lowercase
+        assert_eq!(trie.words(""), vec!["Apple", "apple"]);
+    }
+
+    #[test]
+    fn test_ascii_alphabet() {
+        let mut trie = Trie::builder().alphabet(Alphabet::AsciiLowercase).build();
+        trie.insert("Café");
+        trie.insert("route66");
+        assert_eq!(trie.words(""), vec!["caf", "route66"]);
+        let mut strict = Trie::builder()
+            .alphabet(Alphabet::AsciiLowercase)
+            .case_sensitive(true)
+            .non_alpha(NonAlphaPolicy::Error)
+            .build();
+        assert_eq!(strict.try_insert("apple"), Ok(true));
+        assert_eq!(
+            strict.try_insert("Apple"),
+            Err(TrieError::NonAlphabetic('A'))
+        );
+        assert_eq!(
+            strict.try_insert("naïve"),
+            Err(TrieError::NonAlphabetic('ï'))
+        );
+    }
+
+    #[cfg(feature = "fold-diacritics")]
+    #[test]
+    fn test_fold_diacritics() {
+        let mut trie = Trie::builder().fold_diacritics(true).build();
+        assert!(trie.folds_diacritics());
+        assert!(trie.insert("café"));
+        assert!(!trie.insert("cafe"));
+        assert!(!trie.insert("CAFÉ"));
+        assert!(trie.insert("Ångström"));
+        assert!(trie.contains("angstrom"));
+        trie.insert("crème brûlée");
+        assert!(trie.contains("Creme-Brulee"));
+        assert_eq!(trie.words(""), vec!["angstrom", "cafe", "cremebrulee"]);
+        // Exact matching stays the default
+        let mut exact = Trie::builder().build();
+        exact.insert("café");
+        assert!(!exact.contains("cafe"));
+    }
+
+    #[cfg(feature = "fold-diacritics")]
+    #[test]
+    fn test_fold_diacritics_keeps_distinct_letters() {
+        let mut trie = Trie::builder().fold_diacritics(true).build();
+        // Letters with no decomposition aren't accented forms of another
+        assert!(trie.insert("bøgen"));
+        assert!(trie.insert("bogen"));
+        // Hangul decomposes into jamo, which must be put back together
+        assert!(trie.insert("한국"));
+        assert!(trie.insert("한극"));
+        assert_eq!(trie.len(), 4);
+        assert_eq!(trie.words("한"), vec!["한국", "한극"]);
+        assert_eq!(trie.words("b"), vec!["bogen", "bøgen"]);
+    }
+
+    #[test]
+    fn test_build_map_and_preserve_case() {
+        let mut trie = Trie::builder().preserve_case(true).build_map();
+        trie.insert_value("NASA", 1958);
+        assert_eq!(trie.get("nasa"), Some(&1958));
+        assert_eq!(trie.words(""), vec!["NASA"]);
+    }
+}
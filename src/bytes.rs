@@ -0,0 +1,290 @@
+use alloc::{boxed::Box, collections::BTreeMap, vec, vec::Vec};
+
+/// A trie keyed by raw bytes, for binary identifiers and other keys that
+/// aren't text.
+///
+/// Where [`Trie`](crate::Trie) lowercases its input and filters it through
+/// an [`Alphabet`](crate::Alphabet), a `ByteTrie` stores every byte exactly
+/// as given, including `0` and bytes that aren't valid UTF-8. Like `Trie`,
+/// it never stores the empty key, keeps a count of the keys below each node
+/// and prunes nodes that no longer lead to a key.
+///
+/// ```
+/// use trie::ByteTrie;
+///
+/// let mut trie = ByteTrie::new();
+/// trie.insert_bytes(b"\x00\xff");
+/// trie.insert_bytes(b"\x00\x01");
+/// assert!(trie.contains_bytes(&[0, 255]));
+/// assert_eq!(trie.count_with_prefix(b"\x00"), 2);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ByteTrie {
+    root: ByteNode,
+    len: usize,
+}
+
+#[derive(Debug, Default)]
+struct ByteNode {
+    children: BTreeMap<u8, Box<ByteNode>>,
+    is_end_of_key: bool,
+    // Keys in this subtree, including this node's own
+    key_count: usize,
+}
+
+impl ByteTrie {
+    /// Creates an empty byte trie.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts a key, returning `true` if it wasn't stored before.
+    ///
+    /// The empty key is ignored and returns `false`.
+    pub fn insert_bytes(&mut self, key: &[u8]) -> bool {
+        if key.is_empty() || self.contains_bytes(key) {
+            return false;
+        }
+        // The key is known to be new, so it's counted on the way down
+        let mut current_node = &mut self.root;
+        current_node.key_count += 1;
+        for byte in key {
+            current_node = current_node.children.entry(*byte).or_default();
+            current_node.key_count += 1;
+        }
+        current_node.is_end_of_key = true;
+        self.len += 1;
+        true
+    }
+
+    /// Returns `true` if `key` was inserted as a whole key.
+    pub fn contains_bytes(&self, key: &[u8]) -> bool {
+        self.find(key).is_some_and(|node| node.is_end_of_key)
+    }
+
+    /// Returns `true` if any stored key starts with `prefix`.
+    pub fn starts_with_bytes(&self, prefix: &[u8]) -> bool {
+        self.count_with_prefix(prefix) > 0
+    }
+
+    /// Returns how many stored keys start with `prefix`, without visiting
+    /// them.
+    pub fn count_with_prefix(&self, prefix: &[u8]) -> usize {
+        self.find(prefix).map_or(0, |node| node.key_count)
+    }
+
+    /// Returns every stored key starting with `prefix`, in lexicographic
+    /// byte order.
+    pub fn keys_with_prefix(&self, prefix: &[u8]) -> Vec<Vec<u8>> {
+        let mut keys = Vec::new();
+        let Some(start) = self.find(prefix) else {
+            return keys;
+        };
+        let mut path = prefix.to_vec();
+        let mut stack = vec![(start, path.len(), None)];
+        while let Some((node, parent_len, byte)) = stack.pop() {
+            path.truncate(parent_len);
+            path.extend(byte);
+            if node.is_end_of_key {
+                keys.push(path.clone());
+            }
+            let len = path.len();
+            for (byte, child) in node.children.iter().rev() {
+                stack.push((child, len, Some(*byte)));
+            }
+        }
+        keys
+    }
+
+    /// Removes a key, returning `true` if it was present.
+    pub fn remove_bytes(&mut self, key: &[u8]) -> bool {
+        if key.is_empty() || !self.contains_bytes(key) {
+            return false;
+        }
+        // Walk down uncounting the key, and cut off the first subtree that
+        // holds nothing else
+        let mut current_node = &mut self.root;
+        current_node.key_count -= 1;
+        for byte in key {
+            if current_node.children[byte].key_count == 1 {
+                current_node.children.remove(byte);
+                self.len -= 1;
+                return true;
+            }
+            current_node = current_node.children.get_mut(byte).unwrap();
+            current_node.key_count -= 1;
+        }
+        current_node.is_end_of_key = false;
+        self.len -= 1;
+        true
+    }
+
+    /// Returns the number of distinct keys stored.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no keys are stored.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of allocated nodes, including the root.
+    pub fn node_count(&self) -> usize {
+        let mut count = 0;
+        let mut stack = vec![&self.root];
+        while let Some(node) = stack.pop() {
+            count += 1;
+            stack.extend(node.children.values().map(|child| &**child));
+        }
+        count
+    }
+
+    fn find(&self, key: &[u8]) -> Option<&ByteNode> {
+        let mut current_node = &self.root;
+        for byte in key {
+            current_node = current_node.children.get(byte)?;
+        }
+        Some(current_node)
+    }
+}
+
+// Binary keys can be long, so they are torn down with an explicit stack, as
+// for `TrieNode`
+impl Drop for ByteNode {
+    fn drop(&mut self) {
+        let mut stack: Vec<Box<ByteNode>> =
+            core::mem::take(&mut self.children).into_values().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.extend(core::mem::take(&mut node.children).into_values());
+        }
+    }
+}
+
+// Cloned with an explicit stack too. Each copy is attached to its parent once
+// all of its own children are.
+impl Clone for ByteNode {
+    fn clone(&self) -> Self {
+        let copy = |node: &ByteNode| ByteNode {
+            children: BTreeMap::new(),
+            is_end_of_key: node.is_end_of_key,
+            key_count: node.key_count,
+        };
+        let mut stack = vec![(self.children.iter(), copy(self), None)];
+        loop {
+            let (children, _, _) = stack.last_mut().unwrap();
+            if let Some((byte, child)) = children.next() {
+                stack.push((child.children.iter(), copy(child), Some(*byte)));
+                continue;
+            }
+            let (_, node, byte) = stack.pop().unwrap();
+            match (stack.last_mut(), byte) {
+                (Some((_, parent, _)), Some(byte)) => {
+                    parent.children.insert(byte, Box::new(node));
+                }
+                _ => return node,
+            }
+        }
+    }
+}
+
+// Pairs of nodes go on an explicit stack, so long keys can't overflow it.
+// The counts follow from the keys, so only those are compared.
+impl PartialEq for ByteNode {
+    fn eq(&self, other: &Self) -> bool {
+        let mut stack = vec![(self, other)];
+        while let Some((node, other_node)) = stack.pop() {
+            if node.is_end_of_key != other_node.is_end_of_key
+                || node.children.len() != other_node.children.len()
+                || !node.children.keys().eq(other_node.children.keys())
+            {
+                return false;
+            }
+            let pairs = node.children.values().zip(other_node.children.values());
+            stack.extend(pairs.map(|(child, other_child)| (&**child, &**other_child)));
+        }
+        true
+    }
+}
+
+impl Eq for ByteNode {}
+
+impl<K: AsRef<[u8]>> FromIterator<K> for ByteTrie {
+    fn from_iter<I: IntoIterator<Item = K>>(iter: I) -> Self {
+        let mut trie = ByteTrie::new();
+        for key in iter {
+            trie.insert_bytes(key.as_ref());
+        }
+        trie
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{vec, vec::Vec};
+
+    use super::ByteTrie;
+
+    #[test]
+    fn test_binary_keys() {
+        let keys: [&[u8]; 5] = [b"\x00", b"\x00\x00", b"\xff\xfe", b"\xc3\x28", b"plain"];
+        let mut trie: ByteTrie = keys.iter().collect();
+        assert_eq!(trie.len(), 5);
+        for key in keys {
+            assert!(trie.contains_bytes(key));
+            assert!(!trie.insert_bytes(key));
+        }
+        assert!(!trie.contains_bytes(b"\xff"));
+        assert!(trie.starts_with_bytes(b"\xff"));
+        assert!(!trie.contains_bytes(b""));
+        assert!(!trie.insert_bytes(b""));
+        // No case folding or filtering
+        assert!(!trie.contains_bytes(b"PLAIN"));
+        assert_eq!(trie.count_with_prefix(b"\x00"), 2);
+        assert_eq!(trie.count_with_prefix(b""), 5);
+        assert_eq!(
+            trie.keys_with_prefix(b""),
+            vec![
+                b"\x00".to_vec(),
+                b"\x00\x00".to_vec(),
+                b"plain".to_vec(),
+                b"\xc3\x28".to_vec(),
+                b"\xff\xfe".to_vec(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_remove_prunes() {
+        let mut trie: ByteTrie = [&b"\x01\x02\x03"[..], b"\x01", b"\x01\x09"]
+            .iter()
+            .collect();
+        assert_eq!(trie.node_count(), 5);
+        assert!(trie.remove_bytes(b"\x01\x02\x03"));
+        assert!(!trie.remove_bytes(b"\x01\x02\x03"));
+        assert!(!trie.remove_bytes(b"\x01\x02"));
+        assert_eq!(trie.node_count(), 3);
+        assert_eq!(trie.count_with_prefix(b"\x01"), 2);
+        assert!(trie.remove_bytes(b"\x01"));
+        assert!(trie.contains_bytes(b"\x01\x09"));
+        assert_eq!(trie.node_count(), 3);
+        assert!(trie.remove_bytes(b"\x01\x09"));
+        assert!(trie.is_empty());
+        assert_eq!(trie, ByteTrie::new());
+        assert_eq!(trie.node_count(), 1);
+    }
+
+    #[test]
+    fn test_long_key() {
+        let key: Vec<u8> = (0..100_000).map(|i| (i % 256) as u8).collect();
+        let mut trie = ByteTrie::new();
+        assert!(trie.insert_bytes(&key));
+        assert!(trie.contains_bytes(&key));
+        assert_eq!(trie.keys_with_prefix(&key[..10]), vec![key.clone()]);
+        let copy = trie.clone();
+        assert_eq!(copy, trie);
+        assert!(trie.remove_bytes(&key));
+        assert_ne!(copy, trie);
+        assert_eq!(trie.node_count(), 1);
+    }
+}
@@ -0,0 +1,147 @@
+use alloc::{boxed::Box, vec::Vec};
+use core::ops::Index;
+
+use crate::TrieNode;
+
+// The children of a node, as a vector kept sorted by char.
+//
+// Most nodes in a word trie have one or two children, so a sorted vector is
+// both smaller and faster than a map: lookups are a binary search over a
+// handful of entries, and iteration is in alphabetical order for free.
+pub(crate) struct Children<V> {
+    entries: Vec<(char, Box<TrieNode<V>>)>,
+}
+
+impl<V> Default for Children<V> {
+    fn default() -> Self {
+        Children {
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<V> Children<V> {
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Children {
+            entries: Vec::with_capacity(capacity),
+        }
+    }
+
+    fn search(&self, key: &char) -> Result<usize, usize> {
+        self.entries
+            .binary_search_by_key(key, |(char_code, _)| *char_code)
+    }
+
+    pub(crate) fn get(&self, key: &char) -> Option<&TrieNode<V>> {
+        let i = self.search(key).ok()?;
+        Some(&self.entries[i].1)
+    }
+
+    pub(crate) fn get_mut(&mut self, key: &char) -> Option<&mut TrieNode<V>> {
+        let i = self.search(key).ok()?;
+        Some(&mut self.entries[i].1)
+    }
+
+    pub(crate) fn get_or_insert_with(
+        &mut self,
+        key: char,
+        f: impl FnOnce() -> TrieNode<V>,
+    ) -> &mut TrieNode<V> {
+        let i = match self.search(&key) {
+            Ok(i) => i,
+            Err(i) => {
+                self.entries.insert(i, (key, Box::new(f())));
+                i
+            }
+        };
+        &mut self.entries[i].1
+    }
+
+    // Adds or replaces the child for `key`
+    pub(crate) fn insert(&mut self, key: char, node: Box<TrieNode<V>>) {
+        match self.search(&key) {
+            Ok(i) => self.entries[i].1 = node,
+            Err(i) => self.entries.insert(i, (key, node)),
+        }
+    }
+
+    pub(crate) fn remove(&mut self, key: &char) -> Option<Box<TrieNode<V>>> {
+        let i = self.search(key).ok()?;
+        Some(self.entries.remove(i).1)
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    // Number of entries the vector has room for, used or not
+    pub(crate) fn capacity(&self) -> usize {
+        self.entries.capacity()
+    }
+
+    // Size in bytes of one entry
+    pub(crate) const ENTRY_SIZE: usize = core::mem::size_of::<(char, Box<TrieNode<V>>)>();
+
+    pub(crate) fn shrink_to_fit(&mut self) {
+        self.entries.shrink_to_fit();
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub(crate) fn iter(&self) -> impl DoubleEndedIterator<Item = (&char, &TrieNode<V>)> {
+        self.entries
+            .iter()
+            .map(|(char_code, node)| (char_code, &**node))
+    }
+
+    pub(crate) fn keys(&self) -> impl DoubleEndedIterator<Item = &char> {
+        self.entries.iter().map(|(char_code, _)| char_code)
+    }
+
+    pub(crate) fn values(&self) -> impl DoubleEndedIterator<Item = &TrieNode<V>> {
+        self.entries.iter().map(|(_, node)| &**node)
+    }
+
+    pub(crate) fn values_mut(&mut self) -> impl Iterator<Item = &mut TrieNode<V>> {
+        self.entries.iter_mut().map(|(_, node)| &mut **node)
+    }
+
+    pub(crate) fn into_entries(self) -> impl DoubleEndedIterator<Item = (char, Box<TrieNode<V>>)> {
+        self.entries.into_iter()
+    }
+
+    pub(crate) fn into_values(self) -> impl Iterator<Item = Box<TrieNode<V>>> {
+        self.entries.into_iter().map(|(_, node)| node)
+    }
+}
+
+impl<V> Index<&char> for Children<V> {
+    type Output = TrieNode<V>;
+
+    fn index(&self, key: &char) -> &TrieNode<V> {
+        self.get(key).expect("no child for key")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Children;
+    use crate::TrieNode;
+
+    #[test]
+    fn test_kept_sorted() {
+        let mut children: Children<()> = Children::default();
+        for char_code in ['m', 'a', 'z', 'é', 'b'] {
+            children.get_or_insert_with(char_code, TrieNode::new);
+        }
+        children.get_or_insert_with('a', TrieNode::new);
+        let keys: Vec<char> = children.keys().copied().collect();
+        assert_eq!(keys, vec!['a', 'b', 'm', 'z', 'é']);
+        assert!(children.remove(&'m').is_some());
+        assert!(children.remove(&'m').is_none());
+        assert!(children.get(&'z').is_some());
+        assert_eq!(children.len(), 4);
+    }
+}
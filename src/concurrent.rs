@@ -0,0 +1,134 @@
+use std::sync::{PoisonError, RwLock};
+
+use crate::Trie;
+
+/// A [`Trie`] that can be shared between threads, for read-heavy workloads.
+///
+/// Every method takes `&self`. Lookups take a shared read lock, so any
+/// number of them run at the same time; inserts take the write lock and wait
+/// for readers in flight to finish. Wrap it in an `Arc` to share it.
+///
+/// ```
+/// use std::{sync::Arc, thread};
+///
+/// use trie::ConcurrentTrie;
+///
+/// let trie = Arc::new(ConcurrentTrie::new());
+/// trie.insert("apple");
+/// let reader = Arc::clone(&trie);
+/// thread::spawn(move || assert!(reader.contains("apple")))
+///     .join()
+///     .unwrap();
+/// ```
+#[derive(Debug, Default)]
+pub struct ConcurrentTrie {
+    inner: RwLock<Trie>,
+}
+
+impl ConcurrentTrie {
+    /// Creates an empty trie.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts a word, returning `true` if it wasn't stored before. See
+    /// [`Trie::insert`].
+    pub fn insert(&self, word: &str) -> bool {
+        self.write(|trie| trie.insert(word))
+    }
+
+    /// Returns `true` if `word` was inserted as a whole word. See
+    /// [`Trie::contains`].
+    pub fn contains(&self, word: &str) -> bool {
+        self.read(|trie| trie.contains(word))
+    }
+
+    /// Returns up to `limit` words starting with `prefix`. See
+    /// [`Trie::suggest`].
+    pub fn suggest(&self, prefix: &str, limit: usize) -> Vec<String> {
+        self.read(|trie| trie.suggest(prefix, limit))
+    }
+
+    /// Returns every stored word starting with `prefix`. See [`Trie::words`].
+    pub fn words(&self, prefix: &str) -> Vec<String> {
+        self.read(|trie| trie.words(prefix))
+    }
+
+    /// Returns the number of distinct words stored.
+    pub fn len(&self) -> usize {
+        self.read(Trie::len)
+    }
+
+    /// Returns `true` if no words are stored.
+    pub fn is_empty(&self) -> bool {
+        self.read(Trie::is_empty)
+    }
+
+    /// Unwraps the underlying trie.
+    pub fn into_inner(self) -> Trie {
+        self.inner
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    // Trie methods don't panic halfway through an update, so a lock poisoned
+    // by a panicking caller still guards a consistent trie
+    fn read<T>(&self, f: impl FnOnce(&Trie) -> T) -> T {
+        f(&self.inner.read().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    fn write<T>(&self, f: impl FnOnce(&mut Trie) -> T) -> T {
+        f(&mut self.inner.write().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+impl From<Trie> for ConcurrentTrie {
+    fn from(trie: Trie) -> Self {
+        ConcurrentTrie {
+            inner: RwLock::new(trie),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, thread};
+
+    use super::ConcurrentTrie;
+    use crate::Trie;
+
+    #[test]
+    fn test_readers_and_writer() {
+        let base: Trie = ["apple", "ape", "ball"].iter().collect();
+        let trie = Arc::new(ConcurrentTrie::from(base));
+        let writer = {
+            let trie = Arc::clone(&trie);
+            thread::spawn(move || {
+                for i in 0..200 {
+                    trie.insert(&format!("word{i}"));
+                }
+            })
+        };
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let trie = Arc::clone(&trie);
+                thread::spawn(move || {
+                    for _ in 0..200 {
+                        // Words present from the start are always visible
+                        assert!(trie.contains("apple"));
+                        assert_eq!(trie.suggest("ap", 2), vec!["ape", "apple"]);
+                        assert!(!trie.contains("cat"));
+                    }
+                })
+            })
+            .collect();
+        writer.join().unwrap();
+        for reader in readers {
+            reader.join().unwrap();
+        }
+        assert_eq!(trie.len(), 203);
+        assert!(trie.contains("word199"));
+        let trie = Arc::into_inner(trie).unwrap().into_inner();
+        assert_eq!(trie.words("word1").len(), 111);
+    }
+}
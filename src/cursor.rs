@@ -0,0 +1,169 @@
+use alloc::{string::String, vec, vec::Vec};
+
+use crate::{Trie, TrieNode, Words};
+
+/// A position in a [`Trie`] that moves one letter at a time, for
+/// autocompletion as the user types.
+///
+/// Created by [`Trie::cursor`], at the root. Each [`push`](Self::push) steps
+/// down from the current node instead of walking the whole prefix from the
+/// root again, and [`pop`](Self::pop) steps back up, like a backspace.
+///
+/// ```
+/// use trie::Trie;
+///
+/// let trie: Trie = ["car", "cart", "cat"].iter().collect();
+/// let mut cursor = trie.cursor();
+/// assert!(cursor.push('c') && cursor.push('a') && cursor.push('r'));
+/// assert!(cursor.is_word());
+/// assert_eq!(cursor.completions(), vec!["car", "cart"]);
+/// cursor.pop();
+/// assert_eq!(cursor.completions(), vec!["car", "cart", "cat"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Cursor<'a, V = ()> {
+    trie: &'a Trie<V>,
+    // The nodes from the root down to the current one
+    nodes: Vec<&'a TrieNode<V>>,
+    // How many nodes each push added, to undo them one push at a time
+    pushes: Vec<usize>,
+    // The normalized letters leading to the current node
+    prefix: String,
+}
+
+impl<V> Trie<V> {
+    /// Returns a [`Cursor`] at the root, for finding completions one letter
+    /// at a time.
+    pub fn cursor(&self) -> Cursor<'_, V> {
+        Cursor {
+            trie: self,
+            nodes: vec![&self.root],
+            pushes: Vec::new(),
+            prefix: String::new(),
+        }
+    }
+}
+
+impl<'a, V> Cursor<'a, V> {
+    /// Moves down by `char_code`, returning `false`, and staying put, if no
+    /// stored word continues with it.
+    ///
+    /// `char_code` is normalized like any other input, so `'A'` moves to
+    /// `'a'`. A character the trie would skip, such as `'-'`, has nowhere to
+    /// go and also returns `false`, as does one it would reject.
+    pub fn push(&mut self, char_code: char) -> bool {
+        let mut utf8 = [0; 4];
+        let Some(keys) = self
+            .trie
+            .normalize(char_code.encode_utf8(&mut utf8))
+            .filter(|keys| !keys.is_empty())
+        else {
+            return false;
+        };
+        // A letter can lowercase to several, all of which must be there
+        let mut node = self.node();
+        for key in &keys {
+            match node.children.get(key) {
+                Some(child) => node = child,
+                None => return false,
+            }
+        }
+        for key in &keys {
+            let child = self.node().children.get(key).unwrap();
+            self.nodes.push(child);
+            self.prefix.push(*key);
+        }
+        self.pushes.push(keys.len());
+        true
+    }
+
+    /// Undoes the last successful [`push`](Self::push), returning `false`
+    /// if the cursor is already at the root.
+    pub fn pop(&mut self) -> bool {
+        let Some(levels) = self.pushes.pop() else {
+            return false;
+        };
+        for _ in 0..levels {
+            self.nodes.pop();
+            self.prefix.pop();
+        }
+        true
+    }
+
+    /// Returns `true` if a stored word ends at the cursor.
+    pub fn is_word(&self) -> bool {
+        self.node().is_end_of_word()
+    }
+
+    /// Returns the normalized letters from the root to the cursor.
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Returns how many stored words start at the cursor, without visiting
+    /// them.
+    pub fn count(&self) -> usize {
+        self.node().word_count
+    }
+
+    /// Returns every stored word starting at the cursor, in alphabetical
+    /// order, like [`Trie::words`] with the prefix typed so far.
+    pub fn completions(&self) -> Vec<String> {
+        Words::new(Some((self.node(), self.prefix.clone()))).collect()
+    }
+
+    fn node(&self) -> &'a TrieNode<V> {
+        // The root is never popped
+        self.nodes[self.nodes.len() - 1]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Trie;
+
+    #[test]
+    fn test_typing_and_backspacing() {
+        let trie: Trie = ["car", "cart", "cat", "dog"].iter().collect();
+        let mut cursor = trie.cursor();
+        assert_eq!(cursor.completions(), trie.words(""));
+        assert!(!cursor.is_word());
+        assert!(cursor.push('C'));
+        assert!(cursor.push('a'));
+        assert_eq!(cursor.prefix(), "ca");
+        assert_eq!(cursor.count(), 3);
+        // A typo doesn't move the cursor
+        assert!(!cursor.push('x'));
+        assert_eq!(cursor.prefix(), "ca");
+        assert!(cursor.push('t'));
+        assert!(cursor.is_word());
+        assert_eq!(cursor.completions(), vec!["cat"]);
+        assert!(cursor.pop());
+        assert!(cursor.push('r'));
+        assert!(cursor.push('t'));
+        assert_eq!(cursor.completions(), vec!["cart"]);
+        assert!(!cursor.push('s'));
+        for _ in 0..4 {
+            assert!(cursor.pop());
+        }
+        assert!(!cursor.pop());
+        assert_eq!(cursor.prefix(), "");
+        assert!(cursor.push('d'));
+        assert_eq!(cursor.completions(), vec!["dog"]);
+    }
+
+    #[test]
+    fn test_push_normalizes() {
+        let mut trie = Trie::preserving_case();
+        trie.insert("İzmir");
+        trie.insert("Ice");
+        let mut cursor = trie.cursor();
+        assert!(!cursor.push('-'));
+        // `İ` lowercases to `i` and a combining dot, which isn't stored
+        assert!(cursor.push('İ'));
+        assert_eq!(cursor.prefix(), "i");
+        assert_eq!(cursor.completions(), vec!["Ice", "İzmir"]);
+        assert!(cursor.pop());
+        assert_eq!(cursor.count(), 2);
+    }
+}
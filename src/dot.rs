@@ -0,0 +1,90 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt::Write;
+
+use crate::{Trie, TrieNode};
+
+impl<V> Trie<V> {
+    /// Renders the trie as a Graphviz DOT graph.
+    ///
+    /// Every node becomes a graph node, with nodes that end a word drawn as
+    /// double circles, and every edge is labeled with its character. Render
+    /// it with e.g. `dot -Tpng trie.dot -o trie.png`.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph trie {\n");
+        let mut next_id = 0;
+        // Nodes waiting to be written, with the id their parent gave them
+        let mut stack: Vec<(&TrieNode<V>, usize)> = vec![(&self.root, next_id)];
+        while let Some((node, id)) = stack.pop() {
+            let shape = if node.is_end_of_word() {
+                "doublecircle"
+            } else {
+                "circle"
+            };
+            writeln!(dot, "    n{} [label=\"\", shape={}];", id, shape).unwrap();
+            for (char_code, child) in node.children.iter().rev() {
+                next_id += 1;
+                writeln!(
+                    dot,
+                    "    n{} -> n{} [label=\"{}\"];",
+                    id,
+                    next_id,
+                    escape(*char_code)
+                )
+                .unwrap();
+                stack.push((child, next_id));
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+// Quotes and backslashes are the only characters that need escaping inside
+// a double-quoted DOT string
+fn escape(char_code: char) -> String {
+    match char_code {
+        '"' | '\\' => format!("\\{}", char_code),
+        _ => char_code.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Trie;
+
+    #[test]
+    fn test_to_dot_empty() {
+        let trie = Trie::new();
+        assert_eq!(
+            trie.to_dot(),
+            "digraph trie {\n    n0 [label=\"\", shape=circle];\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let trie: Trie = ["a", "ab", "c"].iter().collect();
+        let dot = trie.to_dot();
+        assert!(dot.starts_with("digraph trie {\n"));
+        assert!(dot.ends_with("}\n"));
+        // One line per node and one per edge
+        assert_eq!(dot.matches("shape=").count(), trie.node_count());
+        assert_eq!(dot.matches(" -> ").count(), trie.node_count() - 1);
+        assert_eq!(dot.matches("doublecircle").count(), trie.len());
+        assert!(dot.contains("[label=\"a\"]"));
+        assert!(dot.contains("[label=\"b\"]"));
+        assert!(dot.contains("[label=\"c\"]"));
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(super::escape('"'), "\\\"");
+        assert_eq!(super::escape('\\'), "\\\\");
+        assert_eq!(super::escape('é'), "é");
+    }
+}
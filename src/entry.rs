@@ -0,0 +1,276 @@
+use alloc::{string::String, vec::Vec};
+
+use crate::{Trie, TrieNode};
+
+/// A view into a single word of a [`Trie`], which is either stored or not.
+///
+/// Created by [`Trie::entry`]. Like `HashMap`'s entries, this updates or
+/// fills in a word's value in one call, instead of a [`Trie::get_mut`]
+/// followed by a [`Trie::insert_value`]:
+///
+/// ```
+/// use trie::Trie;
+///
+/// let mut counts = Trie::<u32>::default();
+/// for word in "the cat saw the dog".split(' ') {
+///     *counts.entry(word).unwrap().or_insert(0) += 1;
+/// }
+/// assert_eq!(counts.get("the"), Some(&2));
+/// assert_eq!(counts.get("cat"), Some(&1));
+/// ```
+pub enum Entry<'a, V> {
+    /// The word is stored.
+    Occupied(OccupiedEntry<'a, V>),
+    /// The word isn't stored yet.
+    Vacant(VacantEntry<'a, V>),
+}
+
+/// A stored word, as part of an [`Entry`].
+pub struct OccupiedEntry<'a, V> {
+    node: &'a mut TrieNode<V>,
+    // The insert counts of the nodes above the word, root first, borrowed
+    // apart from their children so that replacing the value can count the
+    // insert along the path
+    passes: Vec<&'a mut u32>,
+    preserve_case: bool,
+    // The spelling to keep if the value is replaced
+    original: Option<String>,
+    word: String,
+}
+
+/// A word that isn't stored yet, as part of an [`Entry`].
+///
+/// No nodes are created for it until a value is inserted, so dropping a
+/// vacant entry leaves the trie untouched.
+pub struct VacantEntry<'a, V> {
+    trie: &'a mut Trie<V>,
+    keys: Vec<char>,
+    word: String,
+}
+
+impl<V> Trie<V> {
+    /// Gets the entry for `word`, for in-place updates.
+    ///
+    /// `word` is normalized like [`insert_value`](Self::insert_value). Input
+    /// that would be ignored on insert has no entry and returns `None`.
+    pub fn entry(&mut self, word: &str) -> Option<Entry<'_, V>> {
+        let keys = self.normalize_word(word)?;
+        // Checking first keeps the borrow checker happy about handing out
+        // either the node or the whole trie
+        if !self.find_keys(&keys).is_some_and(TrieNode::is_end_of_word) {
+            return Some(Entry::Vacant(VacantEntry {
+                trie: self,
+                keys,
+                word: String::from(word),
+            }));
+        }
+        let original = self.original_spelling(word, &keys);
+        let mut passes = Vec::with_capacity(keys.len());
+        let mut current_node = &mut self.root;
+        for char_code in &keys {
+            let TrieNode {
+                children,
+                passes: node_passes,
+                ..
+            } = current_node;
+            passes.push(node_passes);
+            current_node = children.get_mut(char_code)?;
+        }
+        Some(Entry::Occupied(OccupiedEntry {
+            node: current_node,
+            passes,
+            preserve_case: self.preserve_case,
+            original,
+            word: String::from(word),
+        }))
+    }
+
+    /// Returns the value for `word`, first inserting the result of `f` if it
+    /// isn't stored. `f` is only called for a new word.
+    ///
+    /// Shorthand for `entry(word)` followed by
+    /// [`Entry::or_insert_with`], returning `None` for input that has no
+    /// entry.
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let mut trie = Trie::<Vec<u32>>::default();
+    /// trie.get_or_insert_with("page", Vec::new).unwrap().push(3);
+    /// trie.get_or_insert_with("page", || unreachable!()).unwrap().push(7);
+    /// assert_eq!(trie.get("page"), Some(&vec![3, 7]));
+    /// ```
+    pub fn get_or_insert_with(&mut self, word: &str, f: impl FnOnce() -> V) -> Option<&mut V> {
+        Some(self.entry(word)?.or_insert_with(f))
+    }
+}
+
+impl<'a, V> Entry<'a, V> {
+    /// Returns the value, first inserting `default` if the word isn't stored.
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    /// Returns the value, first inserting the result of `default` if the
+    /// word isn't stored.
+    pub fn or_insert_with(self, default: impl FnOnce() -> V) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Calls `f` on the value if the word is stored.
+    pub fn and_modify(mut self, f: impl FnOnce(&mut V)) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+
+    /// Returns the word as it was passed to [`Trie::entry`].
+    pub fn word(&self) -> &str {
+        match self {
+            Entry::Occupied(entry) => &entry.word,
+            Entry::Vacant(entry) => &entry.word,
+        }
+    }
+}
+
+impl<'a, V: Default> Entry<'a, V> {
+    /// Returns the value, first inserting `V::default()` if the word isn't
+    /// stored.
+    pub fn or_default(self) -> &'a mut V {
+        self.or_insert_with(V::default)
+    }
+}
+
+impl<'a, V> OccupiedEntry<'a, V> {
+    /// Returns a reference to the value.
+    pub fn get(&self) -> &V {
+        self.node.value.as_ref().unwrap()
+    }
+
+    /// Returns a mutable reference to the value.
+    pub fn get_mut(&mut self) -> &mut V {
+        self.node.value.as_mut().unwrap()
+    }
+
+    /// Converts the entry into a mutable reference to the value, borrowed
+    /// from the trie.
+    pub fn into_mut(self) -> &'a mut V {
+        self.node.value.as_mut().unwrap()
+    }
+
+    /// Replaces the value, returning the old one.
+    ///
+    /// This counts as inserting the word again, as with
+    /// [`Trie::insert_value`].
+    pub fn insert(&mut self, value: V) -> V {
+        for passes in &mut self.passes {
+            **passes = passes.saturating_add(1);
+        }
+        self.node.passes = self.node.passes.saturating_add(1);
+        if self.preserve_case {
+            self.node.original.clone_from(&self.original);
+        }
+        self.node.count = self.node.count.saturating_add(1);
+        self.node.value.replace(value).unwrap()
+    }
+}
+
+impl<'a, V> VacantEntry<'a, V> {
+    /// Stores the word with `value`, returning a mutable reference to it.
+    pub fn insert(self, value: V) -> &'a mut V {
+        let Ok(node) = self
+            .trie
+            .insert_keys_counted(&self.keys, &self.word, value, 1)
+        else {
+            unreachable!("a vacant entry's word isn't stored");
+        };
+        node.value.as_mut().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Entry;
+    use crate::Trie;
+
+    #[test]
+    fn test_entry_counts_words() {
+        let mut counts = Trie::<u32>::default();
+        for word in ["to", "be", "or", "not", "to", "Be"] {
+            counts
+                .entry(word)
+                .unwrap()
+                .and_modify(|count| *count += 1)
+                .or_insert(1);
+        }
+        assert_eq!(counts.get("to"), Some(&2));
+        assert_eq!(counts.get("be"), Some(&2));
+        assert_eq!(counts.get("not"), Some(&1));
+        assert_eq!(counts.len(), 4);
+        assert_eq!(counts.count_words_with_prefix("t"), 1);
+        assert_eq!(counts.count_words_with_prefix(""), 4);
+        assert_eq!(counts.prefix_frequency(""), 4);
+        assert!(counts.entry("!!").is_none());
+    }
+
+    #[test]
+    fn test_vacant_entry_leaves_trie_untouched() {
+        let mut trie = Trie::<u32>::default();
+        trie.insert_value("car", 1);
+        let before = trie.clone();
+        match trie.entry("cart").unwrap() {
+            Entry::Vacant(entry) => assert_eq!(entry.word, "cart"),
+            Entry::Occupied(_) => panic!("cart isn't stored"),
+        }
+        assert_eq!(trie, before);
+        assert_eq!(trie.node_count(), 4);
+        // A prefix node without a word is vacant too
+        assert!(matches!(trie.entry("ca"), Some(Entry::Vacant(_))));
+        *trie.entry("ca").unwrap().or_default() += 5;
+        assert_eq!(trie.get("ca"), Some(&5));
+        assert_eq!(trie.words("c"), vec!["ca", "car"]);
+    }
+
+    #[test]
+    fn test_occupied_entry_insert() {
+        let mut trie = Trie {
+            preserve_case: true,
+            ..Trie::<&str>::default()
+        };
+        trie.insert_value("Nasa", "old");
+        let Some(Entry::Occupied(mut entry)) = trie.entry("NASA") else {
+            panic!("NASA is stored");
+        };
+        assert_eq!(*entry.get(), "old");
+        assert_eq!(entry.insert("new"), "old");
+        assert_eq!(trie.get("nasa"), Some(&"new"));
+        assert_eq!(trie.words(""), vec!["NASA"]);
+        assert_eq!(trie.most_common("", 1), vec![(String::from("NASA"), 2)]);
+        assert_eq!(trie.prefix_frequency(""), 2);
+        assert_eq!(trie.prefix_frequency("nas"), 2);
+    }
+
+    #[test]
+    fn test_get_or_insert_with() {
+        let mut trie = Trie::<u32>::default();
+        let mut calls = 0;
+        for word in ["cat", "Cat", "cart", "c-a-t"] {
+            *trie
+                .get_or_insert_with(word, || {
+                    calls += 1;
+                    10
+                })
+                .unwrap() += 1;
+        }
+        assert_eq!(calls, 2);
+        assert_eq!(trie.get("cat"), Some(&13));
+        assert_eq!(trie.get("cart"), Some(&11));
+        assert_eq!(trie.len(), 2);
+        assert_eq!(trie.count_words_with_prefix("ca"), 2);
+        assert!(trie.get_or_insert_with("", || 0).is_none());
+    }
+}
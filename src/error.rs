@@ -0,0 +1,54 @@
+use core::fmt;
+
+/// Errors reported by the `try_` methods of [`Trie`](crate::Trie).
+///
+/// Each variant is a condition the infallible methods handle quietly, by
+/// ignoring the word or treating it as absent:
+///
+/// ```
+/// use trie::{NonAlphaPolicy, Trie, TrieError};
+///
+/// let mut trie = Trie::builder()
+///     .non_alpha(NonAlphaPolicy::Error)
+///     .max_word_length(5)
+///     .build();
+/// assert_eq!(trie.try_insert(""), Err(TrieError::EmptyWord));
+/// assert_eq!(trie.try_insert("ok!"), Err(TrieError::NonAlphabetic('!')));
+/// assert_eq!(
+///     trie.try_insert("lengthy"),
+///     Err(TrieError::WordTooLong { len: 7, max: 5 })
+/// );
+/// assert!(!trie.insert(""));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrieError {
+    /// Nothing was left of the word once normalized, as with `""`, or
+    /// `"!!!"` under [`NonAlphaPolicy::Skip`](crate::NonAlphaPolicy::Skip).
+    /// The empty word is never stored, so inserting it does nothing.
+    /// Prefix queries such as [`Trie::try_words`](crate::Trie::try_words)
+    /// accept the empty prefix and don't report this.
+    EmptyWord,
+    /// The input contained a character that isn't a letter or digit while
+    /// the trie uses [`NonAlphaPolicy::Error`](crate::NonAlphaPolicy::Error).
+    NonAlphabetic(char),
+    /// The word has `len` characters once normalized, more than the trie's
+    /// [`max_word_length`](crate::Trie::max_word_length) of `max`, and the
+    /// trie uses [`LengthPolicy::Reject`](crate::LengthPolicy::Reject).
+    WordTooLong { len: usize, max: usize },
+}
+
+impl fmt::Display for TrieError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrieError::EmptyWord => write!(f, "word is empty once normalized"),
+            TrieError::NonAlphabetic(char_code) => {
+                write!(f, "unsupported character {:?}", char_code)
+            }
+            TrieError::WordTooLong { len, max } => {
+                write!(f, "word of {len} characters exceeds the limit of {max}")
+            }
+        }
+    }
+}
+
+impl core::error::Error for TrieError {}
@@ -0,0 +1,320 @@
+use alloc::{string::String, vec::Vec};
+
+use crate::{Trie, TrieNode};
+
+impl<V> Trie<V> {
+    /// Returns every stored word within Levenshtein distance `max_distance`
+    /// of `word`, in alphabetical order.
+    ///
+    /// One row of the edit-distance table is computed per trie edge while
+    /// descending, so shared prefixes are only scored once. A subtree is
+    /// skipped as soon as every entry of its row exceeds `max_distance`,
+    /// since distances can only grow further down.
+    pub fn fuzzy(&self, word: &str, max_distance: usize) -> Vec<String> {
+        let Some(query) = self.normalize(word) else {
+            return Vec::new();
+        };
+        let first_row: Vec<usize> = (0..=query.len()).collect();
+        let mut matches = Vec::new();
+        if self.root.is_end_of_word() && first_row[query.len()] <= max_distance {
+            matches.push(self.root.display_word(String::new()));
+        }
+        // Nodes still to score, each with the character leading to it, the
+        // length of its parent's path and its parent's row. Children are
+        // pushed in reverse so they come off in alphabetical order, and an
+        // explicit stack keeps long words from recursing.
+        let mut path = String::new();
+        let mut stack: Vec<(&TrieNode<V>, char, usize, Vec<usize>)> = Vec::new();
+        for (char_code, child) in self.root.children.iter().rev() {
+            stack.push((child, *char_code, 0, first_row.clone()));
+        }
+        while let Some((node, char_code, parent_len, previous_row)) = stack.pop() {
+            let mut row = Vec::with_capacity(previous_row.len());
+            row.push(previous_row[0] + 1);
+            for (i, query_char) in query.iter().enumerate() {
+                let substitution = previous_row[i] + usize::from(*query_char != char_code);
+                let insertion = row[i] + 1;
+                let deletion = previous_row[i + 1] + 1;
+                row.push(substitution.min(insertion).min(deletion));
+            }
+            // Nothing below this node can get closer than the best entry here
+            if row.iter().min().is_some_and(|best| *best > max_distance) {
+                continue;
+            }
+
+            path.truncate(parent_len);
+            path.push(char_code);
+            if node.is_end_of_word() && row[query.len()] <= max_distance {
+                matches.push(node.display_word(path.clone()));
+            }
+            let len = path.len();
+            for (next_char, child) in node.children.iter().rev() {
+                stack.push((child, *next_char, len, row.clone()));
+            }
+        }
+        matches
+    }
+
+    /// Returns every stored word within Damerau-Levenshtein distance
+    /// `max_distance` of `word`, with its distance, closest first and
+    /// alphabetically among equals.
+    ///
+    /// Unlike [`fuzzy`](Self::fuzzy), swapping two adjacent letters counts as
+    /// a single edit, which suits typo correction: `"teh"` is one edit away
+    /// from `"the"`. (Each letter takes part in at most one swap, the
+    /// "optimal string alignment" variant.)
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let trie: Trie = ["the", "tan", "then"].iter().collect();
+    /// assert_eq!(
+    ///     trie.suggest_corrections("teh", 1),
+    ///     vec![(String::from("the"), 1)]
+    /// );
+    /// ```
+    pub fn suggest_corrections(&self, word: &str, max_distance: usize) -> Vec<(String, usize)> {
+        let Some(query) = self.normalize(word) else {
+            return Vec::new();
+        };
+        let first_row: Vec<usize> = (0..=query.len()).collect();
+        let mut matches = Vec::new();
+        // Like the stack in `fuzzy`, but a swap of a node's character with
+        // the one before it reaches back two rows, so each node also carries
+        // its parent's character and the row before its parent's
+        let mut path = String::new();
+        let mut stack = Vec::new();
+        for (char_code, child) in self.root.children.iter().rev() {
+            stack.push((child, *char_code, None, 0, first_row.clone(), Vec::new()));
+        }
+        while let Some((
+            node,
+            char_code,
+            previous_char,
+            parent_len,
+            previous_row,
+            row_before_previous,
+        )) = stack.pop()
+        {
+            let mut row = Vec::with_capacity(previous_row.len());
+            row.push(previous_row[0] + 1);
+            for (i, query_char) in query.iter().enumerate() {
+                let substitution = previous_row[i] + usize::from(*query_char != char_code);
+                let insertion = row[i] + 1;
+                let deletion = previous_row[i + 1] + 1;
+                let mut distance = substitution.min(insertion).min(deletion);
+                // The last two trie characters are the last two query
+                // characters swapped
+                if let Some(previous_char) = previous_char
+                    && i > 0
+                    && previous_char == *query_char
+                    && char_code == query[i - 1]
+                {
+                    distance = distance.min(row_before_previous[i - 1] + 1);
+                }
+                row.push(distance);
+            }
+            // A swap can still reach back past this row to the previous one,
+            // so both have to be out of range before the subtree can be
+            // skipped
+            let best = row.iter().min().copied().unwrap_or(usize::MAX);
+            let best_before = previous_row.iter().min().copied().unwrap_or(usize::MAX);
+            if best > max_distance && best_before >= max_distance {
+                continue;
+            }
+
+            path.truncate(parent_len);
+            path.push(char_code);
+            let distance = row[query.len()];
+            if node.is_end_of_word() && distance <= max_distance {
+                matches.push((node.display_word(path.clone()), distance));
+            }
+            let len = path.len();
+            for (next_char, child) in node.children.iter().rev() {
+                stack.push((
+                    child,
+                    *next_char,
+                    Some(char_code),
+                    len,
+                    row.clone(),
+                    previous_row.clone(),
+                ));
+            }
+        }
+        // Stable, so equal distances stay alphabetical
+        matches.sort_by_key(|(_, distance)| *distance);
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Trie;
+
+    // Plain two-row Levenshtein to check the trie version against
+    fn levenshtein(a: &str, b: &str) -> usize {
+        let b: Vec<char> = b.chars().collect();
+        let mut row: Vec<usize> = (0..=b.len()).collect();
+        for (i, a_char) in a.chars().enumerate() {
+            let mut next = vec![i + 1];
+            for (j, b_char) in b.iter().enumerate() {
+                let cost = usize::from(a_char != *b_char);
+                next.push((row[j] + cost).min(row[j + 1] + 1).min(next[j] + 1));
+            }
+            row = next;
+        }
+        row[b.len()]
+    }
+
+    // Optimal string alignment distance, the full-table version
+    fn damerau(a: &str, b: &str) -> usize {
+        let a: Vec<char> = a.chars().collect();
+        let b: Vec<char> = b.chars().collect();
+        let mut table = vec![vec![0; b.len() + 1]; a.len() + 1];
+        for (i, row) in table.iter_mut().enumerate() {
+            row[0] = i;
+        }
+        for (j, cell) in table[0].iter_mut().enumerate() {
+            *cell = j;
+        }
+        for i in 1..=a.len() {
+            for j in 1..=b.len() {
+                let cost = usize::from(a[i - 1] != b[j - 1]);
+                let mut distance = (table[i - 1][j - 1] + cost)
+                    .min(table[i - 1][j] + 1)
+                    .min(table[i][j - 1] + 1);
+                if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                    distance = distance.min(table[i - 2][j - 2] + 1);
+                }
+                table[i][j] = distance;
+            }
+        }
+        table[a.len()][b.len()]
+    }
+
+    #[test]
+    fn test_fuzzy_single_edit() {
+        let trie: Trie = ["apple", "ball"].iter().collect();
+        assert_eq!(trie.fuzzy("aple", 1), vec!["apple"]);
+        let trie: Trie = ["apple", "ape", "ball"].iter().collect();
+        assert_eq!(trie.fuzzy("aple", 1), vec!["ape", "apple"]);
+        assert_eq!(trie.fuzzy("appel", 2), vec!["ape", "apple"]);
+        assert_eq!(trie.fuzzy("APE", 1), vec!["ape"]);
+        assert!(trie.fuzzy("xyz", 1).is_empty());
+    }
+
+    #[test]
+    fn test_fuzzy_distance_zero_is_exact() {
+        let trie: Trie = ["apple", "apply", "ape"].iter().collect();
+        assert_eq!(trie.fuzzy("apple", 0), vec!["apple"]);
+        assert!(trie.fuzzy("appl", 0).is_empty());
+    }
+
+    #[test]
+    fn test_fuzzy_transposition_costs_two() {
+        let trie: Trie = ["the"].iter().collect();
+        assert!(trie.fuzzy("teh", 1).is_empty());
+        assert_eq!(trie.fuzzy("teh", 2), vec!["the"]);
+    }
+
+    #[test]
+    fn test_fuzzy_large_dictionary() {
+        let letters = ['a', 'b', 'c', 'd', 'e'];
+        let mut words = Vec::new();
+        for a in letters {
+            for b in letters {
+                for c in letters {
+                    for d in letters {
+                        words.push(format!("{a}{b}{c}{d}"));
+                    }
+                }
+            }
+        }
+        let trie: Trie = words.iter().collect();
+        for (query, max_distance) in [("abcd", 1), ("eeee", 2), ("abc", 1), ("abcde", 0)] {
+            let expected: Vec<String> = words
+                .iter()
+                .filter(|word| levenshtein(query, word) <= max_distance)
+                .cloned()
+                .collect();
+            assert_eq!(trie.fuzzy(query, max_distance), expected);
+        }
+    }
+
+    #[test]
+    fn test_fuzzy_very_long_word() {
+        // Deep enough to overflow the stack with a recursive traversal
+        let word = "ab".repeat(25_000);
+        let trie: Trie = [word.as_str(), "abc"].iter().collect();
+        assert_eq!(
+            trie.fuzzy("ab", 50_000),
+            vec![word.clone(), String::from("abc")]
+        );
+        assert_eq!(trie.fuzzy(&word[..3], 1), vec!["abc"]);
+    }
+
+    #[test]
+    fn test_suggest_corrections_transposition_costs_one() {
+        let trie: Trie = ["the", "then", "tea", "ten"].iter().collect();
+        assert!(trie.fuzzy("teh", 1).iter().all(|word| word != "the"));
+        assert_eq!(
+            trie.suggest_corrections("teh", 1),
+            vec![
+                (String::from("tea"), 1),
+                (String::from("ten"), 1),
+                (String::from("the"), 1),
+            ]
+        );
+        assert_eq!(
+            trie.suggest_corrections("hten", 1),
+            vec![(String::from("ten"), 1), (String::from("then"), 1)]
+        );
+        assert_eq!(
+            trie.suggest_corrections("the", 1),
+            vec![(String::from("the"), 0), (String::from("then"), 1),]
+        );
+        assert!(trie.suggest_corrections("xyz", 1).is_empty());
+    }
+
+    #[test]
+    fn test_suggest_corrections_very_long_word() {
+        // Deep enough to overflow the stack with a recursive traversal
+        let word = "ab".repeat(25_000);
+        let trie: Trie = [word.as_str(), "abc"].iter().collect();
+        assert_eq!(
+            trie.suggest_corrections("ba", 50_000),
+            vec![(String::from("abc"), 2), (word.clone(), 49_998)]
+        );
+        assert_eq!(
+            trie.suggest_corrections("acb", 1),
+            vec![(String::from("abc"), 1)]
+        );
+    }
+
+    #[test]
+    fn test_suggest_corrections_large_dictionary() {
+        let letters = ['a', 'b', 'c', 'd'];
+        let mut words = Vec::new();
+        for a in letters {
+            for b in letters {
+                for c in letters {
+                    words.push(format!("{a}{b}{c}"));
+                    for d in letters {
+                        words.push(format!("{a}{b}{c}{d}"));
+                    }
+                }
+            }
+        }
+        let trie: Trie = words.iter().collect();
+        for (query, max_distance) in [("abcd", 1), ("badc", 2), ("ba", 1), ("dcba", 0)] {
+            let mut expected: Vec<(String, usize)> = words
+                .iter()
+                .map(|word| (word.clone(), damerau(query, word)))
+                .filter(|(_, distance)| *distance <= max_distance)
+                .collect();
+            expected.sort();
+            expected.sort_by_key(|(_, distance)| *distance);
+            assert_eq!(trie.suggest_corrections(query, max_distance), expected);
+        }
+    }
+}
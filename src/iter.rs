@@ -0,0 +1,236 @@
+use alloc::{string::String, vec, vec::Vec};
+
+use crate::TrieNode;
+
+/// Lazy iterator over stored words, in alphabetical order.
+///
+/// Created by [`Trie::iter`](crate::Trie::iter),
+/// [`Trie::iter_prefix`](crate::Trie::iter_prefix) or by iterating over
+/// `&Trie`. Words are built one at a time as the traversal reaches them, so
+/// taking only the first few completions never visits the rest of the trie.
+///
+/// It can also run backwards from the alphabetically last word, e.g. with
+/// `.rev()`, and knows exactly how many words are left.
+pub struct Words<'a, V> {
+    // Nodes still to visit, each with the length of its parent's word in
+    // `path` and the character on the edge leading to it (none for the
+    // starting node). The top of the stack is always the next node in
+    // alphabetical order.
+    stack: Vec<(&'a TrieNode<V>, usize, Option<char>)>,
+    // The word spelled by the path to the node visited last. Sharing one
+    // buffer keeps deep tries from copying their whole prefix at every level.
+    path: String,
+    // The same for walking backwards. A node is pushed a second time, marked
+    // `true`, to be reported once all its children have been.
+    back_stack: Vec<(&'a TrieNode<V>, usize, Option<char>, bool)>,
+    back_path: String,
+    // Words not yet yielded from either end. Both ends walk the same
+    // sequence, so this is what stops them from crossing.
+    remaining: usize,
+}
+
+impl<'a, V> Words<'a, V> {
+    pub(crate) fn new(start: Option<(&'a TrieNode<V>, String)>) -> Self {
+        match start {
+            Some((node, prefix)) => Words {
+                stack: vec![(node, prefix.len(), None)],
+                back_stack: vec![(node, prefix.len(), None, false)],
+                back_path: prefix.clone(),
+                path: prefix,
+                remaining: node.word_count,
+            },
+            None => Words {
+                stack: Vec::new(),
+                path: String::new(),
+                back_stack: Vec::new(),
+                back_path: String::new(),
+                remaining: 0,
+            },
+        }
+    }
+
+    // Advances to the next word, also handing back its value
+    pub(crate) fn next_entry(&mut self) -> Option<(String, &'a V)> {
+        let (word, node) = self.next_node()?;
+        Some((word, node.value.as_ref()?))
+    }
+
+    // Advances to the next node that ends a word
+    pub(crate) fn next_node(&mut self) -> Option<(String, &'a TrieNode<V>)> {
+        if self.remaining == 0 {
+            return None;
+        }
+        while let Some((node, parent_len, char_val)) = self.stack.pop() {
+            self.path.truncate(parent_len);
+            self.path.extend(char_val);
+            // Push in reverse so the smallest child is visited first
+            let len = self.path.len();
+            for (char_val, child) in node.children.iter().rev() {
+                self.stack.push((child, len, Some(*char_val)));
+            }
+            if node.is_end_of_word() {
+                self.remaining -= 1;
+                return Some((node.display_word(self.path.clone()), node));
+            }
+        }
+        None
+    }
+
+    // The normalized keys leading to the node `next_node` returned last,
+    // whatever spelling the word was reported in
+    pub(crate) fn path(&self) -> &str {
+        &self.path
+    }
+
+    // Steps back to the previous node that ends a word, starting after the
+    // last one
+    pub(crate) fn next_back_node(&mut self) -> Option<(String, &'a TrieNode<V>)> {
+        if self.remaining == 0 {
+            return None;
+        }
+        while let Some((node, parent_len, char_val, expanded)) = self.back_stack.pop() {
+            self.back_path.truncate(parent_len);
+            self.back_path.extend(char_val);
+            if expanded {
+                if node.is_end_of_word() {
+                    self.remaining -= 1;
+                    return Some((node.display_word(self.back_path.clone()), node));
+                }
+                continue;
+            }
+            // A word comes before everything that extends it, so going
+            // backwards it is reported after its children, largest first
+            self.back_stack.push((node, parent_len, char_val, true));
+            let len = self.back_path.len();
+            for (char_val, child) in node.children.iter() {
+                self.back_stack.push((child, len, Some(*char_val), false));
+            }
+        }
+        None
+    }
+}
+
+impl<V> Iterator for Words<'_, V> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        self.next_entry().map(|(word, _)| word)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<V> DoubleEndedIterator for Words<'_, V> {
+    fn next_back(&mut self) -> Option<String> {
+        self.next_back_node().map(|(word, _)| word)
+    }
+}
+
+impl<V> ExactSizeIterator for Words<'_, V> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::Trie;
+
+    #[test]
+    fn test_iter_prefix_sorted() {
+        let mut trie = Trie::new();
+        for word in ["banana", "apple", "ape", "apex", "bandana"] {
+            trie.insert(word);
+        }
+        let words: Vec<String> = trie.iter_prefix("ap").collect();
+        assert_eq!(words, vec!["ape", "apex", "apple"]);
+        let words: Vec<String> = trie.iter_prefix("").collect();
+        assert_eq!(words, vec!["ape", "apex", "apple", "banana", "bandana"]);
+        assert_eq!(trie.iter_prefix("c").next(), None);
+    }
+
+    #[test]
+    fn test_iter_prefix_take_and_drop_early() {
+        let mut trie = Trie::new();
+        for word in ["car", "cart", "carbon", "care", "cat"] {
+            trie.insert(word);
+        }
+        let first: Vec<String> = trie.iter_prefix("car").take(2).collect();
+        assert_eq!(first, vec!["car", "carbon"]);
+        // The iterator only borrows the trie, which stays usable once dropped
+        let mut iter = trie.iter_prefix("ca");
+        assert_eq!(iter.next().as_deref(), Some("car"));
+        drop(iter);
+        trie.insert("cab");
+        assert_eq!(trie.words("cab"), vec!["cab"]);
+    }
+
+    #[test]
+    fn test_into_iterator() {
+        let trie: Trie = ["cab", "apple", "ape"].iter().collect();
+        let mut words = Vec::new();
+        for word in &trie {
+            words.push(word);
+        }
+        assert_eq!(words, vec!["ape", "apple", "cab"]);
+        let lengths: Vec<usize> = (&trie).into_iter().map(|word| word.len()).collect();
+        assert_eq!(lengths, vec![3, 5, 3]);
+    }
+
+    #[test]
+    fn test_into_iterator_empty_and_root_word() {
+        let mut trie = Trie::new();
+        assert_eq!((&trie).into_iter().next(), None);
+        // Input with no alphanumeric characters never becomes a word
+        trie.insert("!!!");
+        trie.insert("a");
+        let words: Vec<String> = trie.iter().collect();
+        assert_eq!(words, vec!["a"]);
+    }
+
+    #[test]
+    fn test_rev_is_descending() {
+        let words = [
+            "ape", "apex", "apple", "b", "ba", "banana", "bandana", "cab",
+        ];
+        let trie: Trie = words.iter().collect();
+        let forward: Vec<String> = trie.iter().collect();
+        assert_eq!(forward, words);
+        let backward: Vec<String> = trie.iter().rev().collect();
+        let mut descending = words.to_vec();
+        descending.reverse();
+        assert_eq!(backward, descending);
+        let backward: Vec<String> = trie.iter_prefix("ba").rev().collect();
+        assert_eq!(backward, vec!["bandana", "banana", "ba"]);
+        assert_eq!(Trie::new().iter().next_back(), None);
+    }
+
+    #[test]
+    fn test_both_ends_meet() {
+        let trie: Trie = ["a", "ab", "abc", "b", "c"].iter().collect();
+        let mut iter = trie.iter();
+        assert_eq!(iter.len(), 5);
+        assert_eq!(iter.next().as_deref(), Some("a"));
+        assert_eq!(iter.next_back().as_deref(), Some("c"));
+        assert_eq!(iter.next_back().as_deref(), Some("b"));
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.next().as_deref(), Some("ab"));
+        assert_eq!(iter.next_back().as_deref(), Some("abc"));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(iter.len(), 0);
+    }
+
+    #[test]
+    fn test_very_long_word() {
+        // Deep enough to overflow the stack with a recursive traversal
+        let word = "ab".repeat(25_000);
+        let mut trie = Trie::new();
+        trie.insert(&word);
+        trie.insert("abc");
+        assert_eq!(
+            trie.words(""),
+            vec!["ab".repeat(25_000), String::from("abc")]
+        );
+        assert_eq!(trie.iter().next_back(), Some(String::from("abc")));
+        assert_eq!(trie.height(), 50_000);
+    }
+}
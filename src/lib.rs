@@ -0,0 +1,4094 @@
+//! A simple trie (prefix tree) for storing and querying words.
+//!
+//! The crate only needs `alloc`. Disable the default `std` feature to build
+//! it for `no_std` targets.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+mod anagram;
+#[cfg(feature = "std")]
+mod binary;
+mod builder;
+mod bytes;
+mod children;
+#[cfg(feature = "std")]
+mod concurrent;
+mod cursor;
+mod dot;
+mod entry;
+mod error;
+mod fuzzy;
+mod iter;
+mod matcher;
+mod minimized;
+mod normalize;
+mod radix;
+#[cfg(feature = "rand")]
+mod random;
+#[cfg(feature = "std")]
+mod reader;
+#[cfg(feature = "serde")]
+mod serde_impl;
+mod stats;
+
+use alloc::{boxed::Box, collections::BTreeSet, string::String, vec, vec::Vec};
+use core::{
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+use children::Children;
+
+pub use builder::TrieBuilder;
+pub use bytes::ByteTrie;
+#[cfg(feature = "std")]
+pub use concurrent::ConcurrentTrie;
+pub use cursor::Cursor;
+pub use entry::{Entry, OccupiedEntry, VacantEntry};
+pub use error::TrieError;
+pub use iter::Words;
+pub use matcher::TextMatcher;
+pub use minimized::MinimizedTrie;
+pub use normalize::{Alphabet, LengthPolicy, NonAlphaPolicy};
+pub use radix::RadixTrie;
+pub use stats::TrieStats;
+
+struct TrieNode<V> {
+    // Ordered by char so traversals yield words in sorted order
+    children: Children<V>,
+    // Set on nodes that end a word
+    value: Option<V>,
+    // Number of words ending at this node or anywhere below it
+    word_count: usize,
+    // How many times the word ending here has been inserted
+    count: u32,
+    // Inserts that went through this node: the sum of `count` over this
+    // node and everything below it
+    passes: u32,
+    // The word as it was inserted, kept only by case-preserving tries
+    original: Option<String>,
+    // When the word ending here was first inserted: the trie's `next_seq`
+    // at the time
+    seq: u64,
+}
+
+impl<V> TrieNode<V> {
+    fn new() -> Self {
+        TrieNode {
+            children: Default::default(),
+            value: None,
+            word_count: 0,
+            count: 0,
+            passes: 0,
+            original: None,
+            seq: 0,
+        }
+    }
+
+    // Restores `passes` from the counts below, after a bulk change to them
+    fn recount_passes(&mut self) {
+        self.passes = self.children.values().fold(self.count, |passes, child| {
+            passes.saturating_add(child.passes)
+        });
+    }
+
+    // The word to report for this node, given the normalized path leading to it
+    fn display_word(&self, path: String) -> String {
+        self.original.clone().unwrap_or(path)
+    }
+
+    fn is_end_of_word(&self) -> bool {
+        self.value.is_some()
+    }
+
+    // A node that ends no word and has no children is no longer needed
+    fn is_prunable(&self) -> bool {
+        !self.is_end_of_word() && self.children.is_empty()
+    }
+
+    // Empties the children, handing back the old ones in letter order
+    fn take_children(
+        &mut self,
+    ) -> impl DoubleEndedIterator<Item = (char, Box<TrieNode<V>>)> + use<V> {
+        let capacity = self.children.len();
+        core::mem::replace(&mut self.children, Children::with_capacity(capacity)).into_entries()
+    }
+
+    // The sequence number of the earliest word at or below this node
+    fn first_seq_below(&self) -> u64 {
+        let mut first = u64::MAX;
+        let mut stack = vec![self];
+        while let Some(node) = stack.pop() {
+            if node.is_end_of_word() {
+                first = first.min(node.seq);
+            }
+            stack.extend(node.children.values());
+        }
+        first
+    }
+}
+
+/// A prefix tree of words, optionally mapping each word to a value.
+///
+/// Words are lowercased on the way in and only letters and digits (in any
+/// script) are kept; punctuation, whitespace and other symbols are skipped
+/// by default (see [`NonAlphaPolicy`]). [`Trie::builder`] changes these
+/// defaults.
+///
+/// `Trie` on its own is a set of words. `Trie<V>` associates a `V` with every
+/// word and is created with [`Trie::default`]:
+///
+/// ```
+/// use trie::Trie;
+///
+/// let mut definitions: Trie<&str> = Trie::default();
+/// definitions.insert_value("trie", "a prefix tree");
+/// assert_eq!(definitions.get("Trie"), Some(&"a prefix tree"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Trie<V = ()> {
+    root: TrieNode<V>,
+    // Number of distinct words, kept up to date by insert/remove
+    len: usize,
+    // Sequence number handed to the next new word
+    next_seq: u64,
+    // Report words with the spelling they were inserted with
+    preserve_case: bool,
+    // Skip lowercasing, so "Apple" and "apple" are different words
+    case_sensitive: bool,
+    alphabet: Alphabet,
+    // Strip accents, so "café" and "cafe" are the same word
+    #[cfg(feature = "fold-diacritics")]
+    fold_diacritics: bool,
+    non_alpha_policy: NonAlphaPolicy,
+    // Longest word accepted, in normalized characters
+    max_word_length: Option<usize>,
+    length_policy: LengthPolicy,
+}
+
+impl<V> Default for Trie<V> {
+    fn default() -> Self {
+        Trie {
+            root: TrieNode::new(),
+            len: 0,
+            next_seq: 0,
+            preserve_case: false,
+            case_sensitive: false,
+            alphabet: Alphabet::Unicode,
+            #[cfg(feature = "fold-diacritics")]
+            fold_diacritics: false,
+            non_alpha_policy: NonAlphaPolicy::Skip,
+            max_word_length: None,
+            length_policy: LengthPolicy::Reject,
+        }
+    }
+}
+
+impl Trie {
+    /// Creates an empty trie.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a [`TrieBuilder`] for configuring a trie before creating it.
+    pub fn builder() -> TrieBuilder {
+        TrieBuilder::new()
+    }
+
+    /// Creates an empty trie that remembers how each word was spelled.
+    ///
+    /// Navigation still goes through the lowercased, filtered form of every
+    /// word, so lookups stay case-insensitive: `contains("APPLE")` finds a
+    /// word inserted as `"Apple"`. Only the words handed back (by `words`,
+    /// `suggest`, iteration and the other word-returning queries) differ:
+    /// they are the exact string passed to the most recent insert of that
+    /// word, punctuation included.
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let mut trie = Trie::preserving_case();
+    /// trie.insert("NASA");
+    /// trie.insert("Nash");
+    /// assert_eq!(trie.words("na"), vec!["NASA", "Nash"]);
+    /// ```
+    pub fn preserving_case() -> Self {
+        Trie {
+            preserve_case: true,
+            ..Self::default()
+        }
+    }
+
+    /// Inserts a word. Input is lowercased and non-alphanumeric characters are skipped.
+    ///
+    /// Returns `true` if the word wasn't stored before, like `HashSet::insert`.
+    /// Input ignored by the [`NonAlphaPolicy`] also returns `false`, as does
+    /// input with nothing left to store once normalized, such as `""` or
+    /// `"!!!"`: the empty word is never stored.
+    pub fn insert(&mut self, word: &str) -> bool {
+        match self.normalize_word(word) {
+            Some(keys) => self.insert_keys(&keys, word, ()).is_none(),
+            None => false,
+        }
+    }
+
+    /// Like [`insert`](Self::insert), but reports input it would ignore
+    /// instead: characters rejected by [`NonAlphaPolicy::Error`], words over
+    /// the length limit and words that are empty once normalized. See
+    /// [`TrieError`] for which gives which error. Input ignored by
+    /// [`NonAlphaPolicy::Reject`] still returns `Ok(false)`.
+    pub fn try_insert(&mut self, word: &str) -> Result<bool, TrieError> {
+        match self.try_normalize_word(word)? {
+            Some(keys) => Ok(self.insert_keys(&keys, word, ()).is_none()),
+            None => Ok(false),
+        }
+    }
+
+    /// Inserts a word as if by `n` calls to [`insert`](Self::insert), in a
+    /// single walk, returning `true` if it wasn't stored before.
+    ///
+    /// The word's count, as reported by [`most_common`](Self::most_common),
+    /// goes up by `n`, so frequency lists load without repeating each word.
+    /// A count of 0 inserts nothing and returns `false`.
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let mut trie = Trie::new();
+    /// trie.insert_with_count("the", 500);
+    /// trie.insert("the");
+    /// assert_eq!(trie.most_common("", 1), vec![(String::from("the"), 501)]);
+    /// ```
+    pub fn insert_with_count(&mut self, word: &str, n: u32) -> bool {
+        if n == 0 {
+            return false;
+        }
+        match self.normalize_word(word) {
+            Some(keys) => self.insert_keys_counted(&keys, word, (), n).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Inserts every word from `words`, returning how many weren't stored
+    /// before.
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let mut trie = Trie::new();
+    /// assert_eq!(trie.insert_all(["apple", "ape", "apple"]), 2);
+    /// assert_eq!(trie.insert_all(vec![String::from("ape")]), 0);
+    /// ```
+    pub fn insert_all(&mut self, words: impl IntoIterator<Item = impl AsRef<str>>) -> usize {
+        words
+            .into_iter()
+            .filter(|word| self.insert(word.as_ref()))
+            .count()
+    }
+
+    /// Renames `old` to `new`, returning `true` if `old` was stored.
+    ///
+    /// If `old` isn't stored, or `new` has nothing to store once normalized,
+    /// nothing changes and this returns `false`. When both normalize to the
+    /// same word this is a no-op that only reports whether it is stored.
+    /// Otherwise `new` starts over as a word inserted once, or counts one
+    /// more insert if it was already stored, and `old`'s count is dropped.
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let mut trie: Trie = ["recieve", "deceive"].iter().collect();
+    /// assert!(trie.replace("recieve", "receive"));
+    /// assert_eq!(trie.words(""), vec!["deceive", "receive"]);
+    /// assert!(!trie.replace("recieve", "receive"));
+    /// ```
+    pub fn replace(&mut self, old: &str, new: &str) -> bool {
+        let (Some(old_keys), Some(new_keys)) = (self.normalize_word(old), self.normalize_word(new))
+        else {
+            return false;
+        };
+        if old_keys == new_keys {
+            return self.contains(old);
+        }
+        if !self.remove(old) {
+            return false;
+        }
+        self.insert_keys(&new_keys, new, ());
+        true
+    }
+
+    /// Builds a trie from a list of words, in time linear in their total
+    /// length when they are already sorted.
+    ///
+    /// Consecutive sorted words share a prefix, so instead of walking down
+    /// from the root for every word, the path of the previous word is kept
+    /// open and only unwound as far as the point where the next word differs.
+    /// Input that isn't sorted (after normalization) still gives the same
+    /// trie as inserting each word, it is just sorted first. Duplicates count
+    /// as repeated inserts.
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let trie = Trie::from_sorted(&["ape", "apple", "apply", "ball"]);
+    /// assert_eq!(trie.words("app"), vec!["apple", "apply"]);
+    /// ```
+    pub fn from_sorted(words: &[impl AsRef<str>]) -> Self {
+        let mut trie = Trie::new();
+        // Each word keeps its position, so a repeated word is numbered by
+        // its first occurrence
+        let mut keys: Vec<(Vec<char>, u64)> = words
+            .iter()
+            .filter_map(|word| trie.normalize_word(word.as_ref()))
+            .zip(0..)
+            .collect();
+        trie.next_seq = keys.len() as u64;
+        if !keys.is_sorted() {
+            keys.sort_unstable();
+        }
+        // Nodes along the previous word, not yet attached to their parents.
+        // Anything added to them later sorts after what they already hold.
+        let mut open: Vec<(char, TrieNode<()>)> = Vec::new();
+        for (word, seq) in &keys {
+            let shared = open
+                .iter()
+                .zip(word)
+                .take_while(|((open_char, _), char_code)| open_char == *char_code)
+                .count();
+            Self::close_path(&mut trie.root, &mut open, shared);
+            open.extend(
+                word[shared..]
+                    .iter()
+                    .map(|char_code| (*char_code, TrieNode::new())),
+            );
+            // Words are never empty, so the path holds at least their last node
+            let (_, node) = open.last_mut().unwrap();
+            if node.value.replace(()).is_none() {
+                node.word_count += 1;
+                node.seq = *seq;
+                trie.len += 1;
+            }
+            node.count = node.count.saturating_add(1);
+            node.passes = node.passes.saturating_add(1);
+        }
+        Self::close_path(&mut trie.root, &mut open, 0);
+        trie
+    }
+
+    // Attaches the open nodes deeper than `depth` to their parents, adding
+    // their totals on the way up
+    fn close_path(root: &mut TrieNode<()>, open: &mut Vec<(char, TrieNode<()>)>, depth: usize) {
+        while open.len() > depth {
+            let (char_code, node) = open.pop().unwrap();
+            let parent = match open.last_mut() {
+                Some((_, parent)) => parent,
+                None => &mut *root,
+            };
+            parent.word_count += node.word_count;
+            parent.passes = parent.passes.saturating_add(node.passes);
+            parent.children.insert(char_code, Box::new(node));
+        }
+    }
+
+    /// Cuts every word down to at most `max_depth` characters, turning the
+    /// trie into a dictionary of the prefixes of that length.
+    ///
+    /// Each node at depth `max_depth` becomes a word, if it wasn't already,
+    /// and loses everything below it. Words of different lengths that share
+    /// those first characters collapse into one, so [`len`](Self::len) may
+    /// shrink; shorter words are kept as they are. The new words keep the
+    /// total count of the inserts they absorbed, so
+    /// [`prefix_frequency`](Self::prefix_frequency) is unchanged for every
+    /// prefix that is still there. A depth of 0 would only leave the empty
+    /// word, which is never stored, so it empties the trie.
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let mut trie: Trie = ["cart", "carbon", "cat", "dog", "do"].iter().collect();
+    /// trie.truncate_depth(3);
+    /// assert_eq!(trie.words(""), vec!["car", "cat", "do", "dog"]);
+    /// ```
+    pub fn truncate_depth(&mut self, max_depth: usize) {
+        if max_depth == 0 {
+            self.clear();
+            return;
+        }
+        // Walked with an explicit stack, as the longest word can be far
+        // deeper than `max_depth`
+        let mut root = Box::new(core::mem::replace(&mut self.root, TrieNode::new()));
+        root.word_count = 0;
+        let children = root.take_children();
+        let mut stack = vec![(None, root, children)];
+        loop {
+            let depth = stack.len();
+            let (_, _, children) = stack.last_mut().unwrap();
+            if let Some((char_code, mut child)) = children.next() {
+                if depth == max_depth {
+                    Self::cut_below(&mut child);
+                } else {
+                    child.word_count = usize::from(child.is_end_of_word());
+                }
+                let children = child.take_children();
+                stack.push((Some(char_code), child, children));
+                continue;
+            }
+            let (char_code, node, _) = stack.pop().unwrap();
+            match (stack.last_mut(), char_code) {
+                (Some((_, parent, _)), Some(char_code)) => {
+                    parent.word_count += node.word_count;
+                    parent.children.insert(char_code, node);
+                }
+                _ => {
+                    self.root = *node;
+                    self.len = self.root.word_count;
+                    return;
+                }
+            }
+        }
+    }
+
+    // Turns a node at the cut depth into a word standing for everything
+    // below it
+    fn cut_below(node: &mut TrieNode<()>) {
+        if node.children.is_empty() {
+            return;
+        }
+        if !node.is_end_of_word() {
+            // The shorter word stands for the deeper ones, so it dates from
+            // the earliest of them
+            node.seq = node.first_seq_below();
+        }
+        node.children = Default::default();
+        if node.value.replace(()).is_none() {
+            // A deeper word's spelling doesn't fit the shorter one
+            node.original = None;
+        }
+        node.count = node.passes;
+        node.word_count = 1;
+    }
+}
+
+impl<V> Trie<V> {
+    /// Sets how characters other than letters and digits are handled.
+    ///
+    /// ```
+    /// use trie::{NonAlphaPolicy, Trie};
+    ///
+    /// let mut trie = Trie::new().with_non_alpha_policy(NonAlphaPolicy::Reject);
+    /// trie.insert("ape'");
+    /// assert!(trie.is_empty());
+    /// ```
+    pub fn with_non_alpha_policy(mut self, policy: NonAlphaPolicy) -> Self {
+        self.non_alpha_policy = policy;
+        self
+    }
+
+    /// Returns how characters other than letters and digits are handled.
+    pub fn non_alpha_policy(&self) -> NonAlphaPolicy {
+        self.non_alpha_policy
+    }
+
+    /// Sets the longest word, in characters after normalization, that the
+    /// trie accepts. `None`, the default, means no limit.
+    ///
+    /// Longer words are rejected, or truncated under
+    /// [`LengthPolicy::Truncate`]. A limit keeps one pathological input from
+    /// growing the trie arbitrarily deep.
+    ///
+    /// ```
+    /// use trie::{Trie, TrieError};
+    ///
+    /// let mut trie = Trie::new().with_max_word_length(Some(5));
+    /// assert!(trie.insert("apple"));
+    /// assert!(!trie.insert("apples"));
+    /// assert_eq!(
+    ///     trie.try_insert("apples"),
+    ///     Err(TrieError::WordTooLong { len: 6, max: 5 })
+    /// );
+    /// ```
+    pub fn with_max_word_length(mut self, max: Option<usize>) -> Self {
+        self.max_word_length = max;
+        self
+    }
+
+    /// Sets what happens to words longer than the
+    /// [`max_word_length`](Self::max_word_length).
+    pub fn with_length_policy(mut self, policy: LengthPolicy) -> Self {
+        self.length_policy = policy;
+        self
+    }
+
+    /// Returns the longest word accepted, if there is a limit.
+    pub fn max_word_length(&self) -> Option<usize> {
+        self.max_word_length
+    }
+
+    /// Returns what happens to words over the length limit.
+    pub fn length_policy(&self) -> LengthPolicy {
+        self.length_policy
+    }
+
+    /// Returns which characters are stored.
+    pub fn alphabet(&self) -> Alphabet {
+        self.alphabet
+    }
+
+    /// Returns `true` if words are stored without lowercasing them first.
+    pub fn is_case_sensitive(&self) -> bool {
+        self.case_sensitive
+    }
+
+    /// Returns `true` if accents and other combining marks are stripped from
+    /// words. Enabled by the `fold-diacritics` feature.
+    #[cfg(feature = "fold-diacritics")]
+    pub fn folds_diacritics(&self) -> bool {
+        self.fold_diacritics
+    }
+
+    /// Inserts `word` with an associated value, returning the value it
+    /// previously had, if any.
+    ///
+    /// The word is normalized the same way as [`Trie::insert`]. Input ignored
+    /// by the [`NonAlphaPolicy`], or that normalizes to the empty word, drops
+    /// `value` and returns `None`.
+    pub fn insert_value(&mut self, word: &str, value: V) -> Option<V> {
+        let keys = self.normalize_word(word)?;
+        self.insert_keys(&keys, word, value)
+    }
+
+    /// Like [`insert_value`](Self::insert_value), but reports input it would
+    /// ignore instead, like [`try_insert`](Self::try_insert).
+    pub fn try_insert_value(&mut self, word: &str, value: V) -> Result<Option<V>, TrieError> {
+        let Some(keys) = self.try_normalize_word(word)? else {
+            return Ok(None);
+        };
+        Ok(self.insert_keys(&keys, word, value))
+    }
+
+    fn insert_keys(&mut self, keys: &[char], word: &str, value: V) -> Option<V> {
+        self.insert_keys_counted(keys, word, value, 1).err()
+    }
+
+    // Inserts the word as if `insert` had been called `n` times. A new
+    // word's node is handed back, and a stored word's replaced value as the
+    // error.
+    fn insert_keys_counted(
+        &mut self,
+        keys: &[char],
+        word: &str,
+        value: V,
+        n: u32,
+    ) -> Result<&mut TrieNode<V>, V> {
+        let original = self.original_spelling(word, keys);
+        let mut current_node = &mut self.root;
+        current_node.passes = current_node.passes.saturating_add(n);
+        for char_code in keys {
+            current_node = current_node
+                .children
+                .get_or_insert_with(*char_code, TrieNode::new);
+            current_node.passes = current_node.passes.saturating_add(n);
+        }
+        if self.preserve_case {
+            current_node.original = original;
+        }
+        current_node.count = current_node.count.saturating_add(n);
+        if let Some(previous) = current_node.value.replace(value) {
+            return Err(previous);
+        }
+        current_node.seq = self.next_seq;
+        self.next_seq += 1;
+        self.len += 1;
+        // Only now do we know the word is new, so count it along its path
+        let mut current_node = &mut self.root;
+        current_node.word_count += 1;
+        for char_code in keys {
+            current_node = current_node.children.get_mut(char_code).unwrap();
+            current_node.word_count += 1;
+        }
+        Ok(current_node)
+    }
+
+    /// Returns a reference to the value stored for `word`.
+    pub fn get(&self, word: &str) -> Option<&V> {
+        self.find_keys(&self.normalize_word(word)?)?.value.as_ref()
+    }
+
+    /// Returns a mutable reference to the value stored for `word`.
+    pub fn get_mut(&mut self, word: &str) -> Option<&mut V> {
+        let keys = self.normalize_word(word)?;
+        let mut current_node = &mut self.root;
+        for char_code in &keys {
+            current_node = current_node.children.get_mut(char_code)?;
+        }
+        current_node.value.as_mut()
+    }
+
+    /// Returns the number of distinct words stored.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the number of distinct words stored, the same as
+    /// [`len`](Self::len).
+    ///
+    /// Named to pair with [`total_inserts`](Self::total_inserts).
+    pub fn distinct_len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the number of inserts of the words still stored, counting
+    /// repeated inserts of the same word every time.
+    ///
+    /// This is [`prefix_frequency("")`](Self::prefix_frequency), so
+    /// `total_inserts() as f64 / distinct_len() as f64` is the average number
+    /// of times each word was inserted. Removing a word takes all its
+    /// inserts back out, and like the per-word counts this saturates at
+    /// `u32::MAX`.
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let trie: Trie = ["a", "a", "a", "b"].iter().collect();
+    /// assert_eq!(trie.distinct_len(), 2);
+    /// assert_eq!(trie.total_inserts(), 4);
+    /// ```
+    pub fn total_inserts(&self) -> u32 {
+        self.root.passes
+    }
+
+    /// Returns `true` if no words are stored.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of allocated nodes, including the root.
+    ///
+    /// Every node is its own allocation, so this is also how many
+    /// allocations the trie holds. It is at most one more than the total
+    /// number of characters in the stored words (after normalization), less
+    /// one for every character of a shared prefix, so the total input length
+    /// bounds the memory a word list will take. Nodes are allocated one at a time as
+    /// words arrive, so there is no capacity to reserve up front.
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let trie: Trie = ["car", "cart", "dog"].iter().collect();
+    /// // "cart" only adds the `t` after "car"
+    /// assert_eq!(trie.node_count(), 1 + 3 + 1 + 3);
+    /// ```
+    pub fn node_count(&self) -> usize {
+        let mut count = 0;
+        let mut stack = vec![&self.root];
+        while let Some(node) = stack.pop() {
+            count += 1;
+            stack.extend(node.children.values());
+        }
+        count
+    }
+
+    /// Calls `f` on every node, parents before their children and siblings
+    /// in alphabetical order, with the prefix leading to the node and whether
+    /// a word ends there.
+    ///
+    /// The root comes first, with the empty prefix. Prefixes are in their
+    /// normalized form, even in a case-preserving trie. This is enough to
+    /// compute most statistics about the shape of the trie in one pass:
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let trie: Trie = ["car", "cart", "cat"].iter().collect();
+    /// let mut ends = Vec::new();
+    /// trie.walk(|prefix, is_end_of_word| {
+    ///     if is_end_of_word {
+    ///         ends.push(prefix.len());
+    ///     }
+    /// });
+    /// assert_eq!(ends, vec![3, 4, 3]);
+    /// ```
+    pub fn walk(&self, mut f: impl FnMut(&str, bool)) {
+        let mut path = String::new();
+        let mut stack = vec![(&self.root, 0, None)];
+        while let Some((node, parent_len, char_val)) = stack.pop() {
+            path.truncate(parent_len);
+            path.extend(char_val);
+            f(&path, node.is_end_of_word());
+            let len = path.len();
+            for (char_val, child) in node.children.iter().rev() {
+                stack.push((child, len, Some(*char_val)));
+            }
+        }
+    }
+
+    /// Estimates the heap and inline memory used by the trie, in bytes.
+    ///
+    /// Counts the `Trie` itself, every boxed node, the vectors holding each
+    /// node's children (at their full capacity) and the original spellings
+    /// kept by case-preserving tries. It doesn't include memory owned by the
+    /// values (a `String` value's buffer, say) or the allocator's own
+    /// bookkeeping and rounding, so the real footprint is somewhat higher.
+    pub fn memory_usage(&self) -> usize {
+        let mut bytes = size_of::<Self>();
+        let mut stack = vec![&self.root];
+        while let Some(node) = stack.pop() {
+            bytes += node.children.capacity() * Children::<V>::ENTRY_SIZE;
+            bytes += node.original.as_ref().map_or(0, String::capacity);
+            for child in node.children.values() {
+                bytes += size_of::<TrieNode<V>>();
+                stack.push(child);
+            }
+        }
+        bytes
+    }
+
+    /// Trims the spare capacity left behind by heavy churn.
+    ///
+    /// The children of a node keep room for entries that were removed, and
+    /// case-preserved spellings may hold more than they need; this gives it
+    /// back. Nodes aren't moved, so lookups are unaffected. Takes time linear
+    /// in the number of nodes, and the words, values and counters are
+    /// untouched.
+    pub fn shrink_to_fit(&mut self) {
+        let mut stack = vec![&mut self.root];
+        while let Some(node) = stack.pop() {
+            node.children.shrink_to_fit();
+            if let Some(original) = &mut node.original {
+                original.shrink_to_fit();
+            }
+            stack.extend(node.children.values_mut());
+        }
+    }
+
+    /// Checks the trie's internal bookkeeping, describing the first problem
+    /// found.
+    ///
+    /// Verifies that every node but the root leads to a word, that each
+    /// node's counts of the words and inserts below it add up, that children
+    /// are in order and that [`len`](Self::len) matches a fresh count of the
+    /// stored words. Meant as a test oracle for code that changes the trie,
+    /// so it's only compiled with debug assertions.
+    #[cfg(debug_assertions)]
+    pub fn check_invariants(&self) -> Result<(), String> {
+        let mut words = 0;
+        let mut stack = vec![(&self.root, String::new())];
+        while let Some((node, path)) = stack.pop() {
+            let fail = |problem: String| Err(alloc::format!("node {path:?}: {problem}"));
+            if !path.is_empty() && node.is_prunable() {
+                return fail(String::from("leads to no word"));
+            }
+            if node.is_end_of_word() {
+                words += 1;
+                if node.count == 0 {
+                    return fail(String::from("stores a word inserted 0 times"));
+                }
+                if node.seq >= self.next_seq {
+                    return fail(String::from("has a sequence number not handed out yet"));
+                }
+            } else if node.count != 0 || node.original.is_some() {
+                return fail(String::from("has a count or spelling but no word"));
+            }
+            let word_count = node
+                .children
+                .values()
+                .map(|child| child.word_count)
+                .sum::<usize>()
+                + usize::from(node.is_end_of_word());
+            if node.word_count != word_count {
+                return fail(alloc::format!(
+                    "counts {} words, but {word_count} end at or below it",
+                    node.word_count
+                ));
+            }
+            let passes = node.children.values().fold(node.count, |passes, child| {
+                passes.saturating_add(child.passes)
+            });
+            // Once saturated, removals leave the stored total below the cap
+            if passes != u32::MAX && node.passes != passes {
+                return fail(alloc::format!(
+                    "counts {} inserts, but {passes} went through it",
+                    node.passes
+                ));
+            }
+            if !node.children.keys().is_sorted_by(|a, b| a < b) {
+                return fail(String::from("has children out of order"));
+            }
+            for (char_code, child) in node.children.iter() {
+                let mut child_path = path.clone();
+                child_path.push(*char_code);
+                stack.push((child, child_path));
+            }
+        }
+        if self.len != words {
+            return Err(alloc::format!(
+                "len is {}, but {words} words are stored",
+                self.len
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the length, in characters, of the longest path from the root.
+    ///
+    /// Since empty branches are pruned, this is the length of the longest word.
+    pub fn height(&self) -> usize {
+        let mut height = 0;
+        let mut stack = vec![(&self.root, 0)];
+        while let Some((node, depth)) = stack.pop() {
+            height = height.max(depth);
+            stack.extend(node.children.values().map(|child| (child, depth + 1)));
+        }
+        height
+    }
+
+    /// Returns `true` if `word` was inserted as a whole word.
+    ///
+    /// `word` is normalized exactly like [`insert`](Trie::insert) does, so
+    /// anything that was inserted is found again with the same input.
+    pub fn contains(&self, word: &str) -> bool {
+        self.get(word).is_some()
+    }
+
+    /// Like [`contains`](Self::contains), but reports characters rejected by
+    /// [`NonAlphaPolicy::Error`], words over the length limit and empty
+    /// words instead of returning `false`. See [`TrieError`].
+    pub fn try_contains(&self, word: &str) -> Result<bool, TrieError> {
+        let Some(keys) = self.try_normalize_word(word)? else {
+            return Ok(false);
+        };
+        Ok(self.find_keys(&keys).is_some_and(TrieNode::is_end_of_word))
+    }
+
+    /// Returns `true` if at least one of `words` is stored, stopping at the
+    /// first one found. An empty list contains nothing, so it gives `false`.
+    pub fn contains_any(&self, words: impl IntoIterator<Item = impl AsRef<str>>) -> bool {
+        words.into_iter().any(|word| self.contains(word.as_ref()))
+    }
+
+    /// Returns `true` if every one of `words` is stored, stopping at the
+    /// first one missing. An empty list has nothing missing, so it gives
+    /// `true`.
+    pub fn contains_all(&self, words: impl IntoIterator<Item = impl AsRef<str>>) -> bool {
+        words.into_iter().all(|word| self.contains(word.as_ref()))
+    }
+
+    /// Returns how many edges lead from the root to `word`, or `None` if it
+    /// isn't stored.
+    ///
+    /// That's the length of the word as stored, which can be shorter than
+    /// `word` when characters are skipped by [`NonAlphaPolicy::Skip`] or cut
+    /// by [`LengthPolicy::Truncate`].
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let mut trie = Trie::new();
+    /// trie.insert("a-b-c");
+    /// assert_eq!(trie.depth_of("abc"), Some(3));
+    /// assert_eq!(trie.depth_of("ab"), None);
+    /// ```
+    pub fn depth_of(&self, word: &str) -> Option<usize> {
+        let keys = self.normalize_word(word)?;
+        self.find_keys(&keys)
+            .filter(|node| node.is_end_of_word())
+            .map(|_| keys.len())
+    }
+
+    /// Returns every stored word starting with `prefix`, in alphabetical order.
+    pub fn words(&self, prefix: &str) -> Vec<String> {
+        self.iter_prefix(prefix).collect()
+    }
+
+    /// Like [`words`](Self::words), but reports characters rejected by
+    /// [`NonAlphaPolicy::Error`] instead of returning no words.
+    pub fn try_words(&self, prefix: &str) -> Result<Vec<String>, TrieError> {
+        let Some(keys) = self.try_normalize(prefix)? else {
+            return Ok(Vec::new());
+        };
+        Ok(Words::new(self.start_at(&keys)).collect())
+    }
+
+    /// Returns every stored word starting with `prefix` together with its
+    /// value, in alphabetical order.
+    ///
+    /// Like [`words`](Self::words), but saves a [`get`](Self::get) per
+    /// completion when the values are needed too.
+    pub fn keys_with_values(&self, prefix: &str) -> Vec<(String, &V)> {
+        let mut words = self.iter_prefix(prefix);
+        core::iter::from_fn(|| words.next_entry()).collect()
+    }
+
+    /// Returns what follows `prefix` in every stored word starting with it,
+    /// in alphabetical order.
+    ///
+    /// The suffixes are cut after the normalized prefix, so skipped
+    /// characters in `prefix` don't shift them, and normalized `prefix`
+    /// followed by a suffix is always a stored word. A word equal to the
+    /// prefix gives the empty suffix. Case-preserved spellings cover the whole
+    /// word, so the suffixes are reported normalized.
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let trie: Trie = ["apple", "ape", "bat"].iter().collect();
+    /// assert_eq!(trie.completions_suffixes("ap"), vec!["e", "ple"]);
+    /// assert_eq!(trie.completions_suffixes("a-p"), vec!["e", "ple"]);
+    /// ```
+    pub fn completions_suffixes(&self, prefix: &str) -> Vec<String> {
+        let Some(start) = self.find_prefix(prefix) else {
+            return Vec::new();
+        };
+        let mut suffixes = Vec::with_capacity(start.word_count);
+        // Each node with the length of its parent's suffix and the letter
+        // leading to it, smallest child on top
+        let mut path = String::new();
+        let mut stack = vec![(start, 0, None)];
+        while let Some((node, parent_len, char_val)) = stack.pop() {
+            path.truncate(parent_len);
+            path.extend(char_val);
+            if node.is_end_of_word() {
+                suffixes.push(path.clone());
+            }
+            let len = path.len();
+            for (char_val, child) in node.children.iter().rev() {
+                stack.push((child, len, Some(*char_val)));
+            }
+        }
+        suffixes
+    }
+
+    /// Returns every stored word, in alphabetical order.
+    ///
+    /// Equivalent to `words("")`.
+    pub fn all_words(&self) -> Vec<String> {
+        self.iter().collect()
+    }
+
+    /// Consumes the trie, returning every stored word in alphabetical order.
+    ///
+    /// Gives the same list as [`all_words`](Self::all_words), at about the
+    /// same cost: words are spelled from their paths either way, so there is
+    /// nothing to move out of the nodes except the original spellings of a
+    /// case-preserving trie, which are taken instead of cloned. Nodes are
+    /// freed as soon as they have been visited, so the trie's memory is
+    /// released while the list grows rather than after it is complete.
+    pub fn into_sorted_vec(mut self) -> Vec<String> {
+        let mut words = Vec::with_capacity(self.len);
+        let mut path = String::new();
+        let mut stack: Vec<(usize, char, Box<TrieNode<V>>)> = Vec::new();
+        let children = core::mem::take(&mut self.root.children);
+        stack.extend(
+            children
+                .into_entries()
+                .rev()
+                .map(|(char_val, child)| (0, char_val, child)),
+        );
+        while let Some((parent_len, char_val, mut node)) = stack.pop() {
+            path.truncate(parent_len);
+            path.push(char_val);
+            if node.is_end_of_word() {
+                words.push(node.original.take().unwrap_or_else(|| path.clone()));
+            }
+            let len = path.len();
+            let children = core::mem::take(&mut node.children);
+            // `node` is dropped here, its children already moved to the stack
+            stack.extend(
+                children
+                    .into_entries()
+                    .rev()
+                    .map(|(char_val, child)| (len, char_val, child)),
+            );
+        }
+        words
+    }
+
+    /// Returns every stored word, in alphabetical order. Another name for
+    /// [`all_words`](Self::all_words).
+    pub fn to_vec(&self) -> Vec<String> {
+        self.all_words()
+    }
+
+    /// Returns every stored word as a set, for comparing against other
+    /// collections of words.
+    ///
+    /// ```
+    /// use std::collections::BTreeSet;
+    ///
+    /// use trie::Trie;
+    ///
+    /// let trie: Trie = ["ape", "Apple", "ape"].iter().collect();
+    /// assert_eq!(trie.to_set(), BTreeSet::from(["ape".into(), "apple".into()]));
+    /// ```
+    pub fn to_set(&self) -> BTreeSet<String> {
+        self.iter().collect()
+    }
+
+    /// Returns every stored word ending with `suffix`, in alphabetical order.
+    ///
+    /// `suffix` is normalized like any other input, and an empty suffix
+    /// matches every word.
+    ///
+    /// A trie only indexes prefixes, so this visits every node: the cost is
+    /// that of [`all_words`](Self::all_words), minus building the words that
+    /// don't match. Keeping a second trie of reversed words would answer in
+    /// time proportional to the matches instead, but would double the memory
+    /// and have to follow every update; for heavy suffix lookups, build one
+    /// yourself by inserting each word reversed and querying it with
+    /// [`words`](Self::words).
+    pub fn ends_with(&self, suffix: &str) -> Vec<String> {
+        let Some(keys) = self.normalize(suffix) else {
+            return Vec::new();
+        };
+        let suffix: String = keys.into_iter().collect();
+        let mut words = Vec::new();
+        let mut path = String::new();
+        let mut stack = vec![(&self.root, 0, None)];
+        while let Some((node, parent_len, char_val)) = stack.pop() {
+            path.truncate(parent_len);
+            path.extend(char_val);
+            if node.is_end_of_word() && path.ends_with(&suffix) {
+                words.push(node.display_word(path.clone()));
+            }
+            let len = path.len();
+            for (char_val, child) in node.children.iter().rev() {
+                stack.push((child, len, Some(*char_val)));
+            }
+        }
+        words
+    }
+
+    /// Returns every stored word that is exactly `n` characters long, in
+    /// alphabetical order.
+    ///
+    /// Branches deeper than `n` are never visited, so this is cheaper than
+    /// filtering [`all_words`](Self::all_words). Length counts characters
+    /// after normalization, so `"ape'"` has length 3.
+    pub fn words_of_length(&self, n: usize) -> Vec<String> {
+        let mut words = Vec::new();
+        let mut path = String::new();
+        // Each node with its depth, its parent's length in `path` and the
+        // character leading to it, as in `Words`
+        let mut stack = vec![(&self.root, 0, 0, None)];
+        while let Some((node, depth, parent_len, char_val)) = stack.pop() {
+            path.truncate(parent_len);
+            path.extend(char_val);
+            if depth == n {
+                if node.is_end_of_word() {
+                    words.push(node.display_word(path.clone()));
+                }
+                continue;
+            }
+            let len = path.len();
+            for (char_val, child) in node.children.iter().rev() {
+                stack.push((child, depth + 1, len, Some(*char_val)));
+            }
+        }
+        words
+    }
+
+    /// Returns every stored word starting with `prefix` together with its
+    /// depth, the number of characters it is stored as, in alphabetical
+    /// order.
+    ///
+    /// The depth is tracked during the walk, so it's cheaper than counting
+    /// the characters of each word, and it is the normalized length even
+    /// when the word is reported with its original spelling.
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let trie: Trie = ["car", "cart", "can't"].iter().collect();
+    /// assert_eq!(
+    ///     trie.words_with_depth("ca"),
+    ///     vec![(String::from("cant"), 4), (String::from("car"), 3), (String::from("cart"), 4)]
+    /// );
+    /// ```
+    pub fn words_with_depth(&self, prefix: &str) -> Vec<(String, usize)> {
+        let mut words = Vec::new();
+        let Some(keys) = self.normalize(prefix) else {
+            return words;
+        };
+        let Some(start) = self.find_keys(&keys) else {
+            return words;
+        };
+        let mut path: String = keys.iter().collect();
+        let mut stack = vec![(start, keys.len(), path.len(), None)];
+        while let Some((node, depth, parent_len, char_val)) = stack.pop() {
+            path.truncate(parent_len);
+            path.extend(char_val);
+            if node.is_end_of_word() {
+                words.push((node.display_word(path.clone()), depth));
+            }
+            let len = path.len();
+            for (char_val, child) in node.children.iter().rev() {
+                stack.push((child, depth + 1, len, Some(*char_val)));
+            }
+        }
+        words
+    }
+
+    /// Returns the stored words from `start` up to but not including `end`,
+    /// in alphabetical order.
+    ///
+    /// Both bounds are normalized like a prefix and compared with the words
+    /// as stored. Subtrees that lie wholly before `start` or from `end` on are
+    /// skipped without being visited, so a page of words costs little more
+    /// than the page itself. An empty `start` begins at the first word; a
+    /// `start` that isn't before `end` gives nothing.
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let trie: Trie = ["apple", "banana", "band", "cherry"].iter().collect();
+    /// assert_eq!(trie.words_in_range("b", "bane"), vec!["banana", "band"]);
+    /// assert_eq!(trie.words_in_range("", "b"), vec!["apple"]);
+    /// ```
+    pub fn words_in_range(&self, start: &str, end: &str) -> Vec<String> {
+        let mut words = Vec::new();
+        let (Some(start), Some(end)) = (self.normalize(start), self.normalize(end)) else {
+            return words;
+        };
+        let start: String = start.into_iter().collect();
+        let end: String = end.into_iter().collect();
+        if start >= end {
+            return words;
+        }
+        let mut path = String::new();
+        let mut stack = vec![(&self.root, 0, None)];
+        while let Some((node, parent_len, char_val)) = stack.pop() {
+            path.truncate(parent_len);
+            path.extend(char_val);
+            // Extending `path` only makes it larger, so a path before `start`
+            // that isn't a prefix of it can't lead into the range, and one that
+            // has reached `end` has left it for good
+            if *path < *start && !start.starts_with(&*path) {
+                continue;
+            }
+            if *path >= *end {
+                continue;
+            }
+            if node.is_end_of_word() && *path >= *start {
+                words.push(node.display_word(path.clone()));
+            }
+            let len = path.len();
+            for (char_val, child) in node.children.iter().rev() {
+                stack.push((child, len, Some(*char_val)));
+            }
+        }
+        words
+    }
+
+    /// Returns a word of maximal length, or `None` if the trie is empty.
+    ///
+    /// Among equally long words the alphabetically smallest wins.
+    pub fn longest_word(&self) -> Option<String> {
+        self.extreme_word(|len, best| len > best)
+    }
+
+    /// Returns a word of minimal length, or `None` if the trie is empty.
+    ///
+    /// Among equally short words the alphabetically smallest wins.
+    pub fn shortest_word(&self) -> Option<String> {
+        self.extreme_word(|len, best| len < best)
+    }
+
+    // One alphabetical walk keeping the first word whose length `beats` the
+    // best so far, so ties go to the word seen first
+    fn extreme_word(&self, beats: impl Fn(usize, usize) -> bool) -> Option<String> {
+        let mut best: Option<(usize, String)> = None;
+        let mut path = String::new();
+        let mut stack = vec![(&self.root, 0, 0, None)];
+        while let Some((node, depth, parent_len, char_val)) = stack.pop() {
+            path.truncate(parent_len);
+            path.extend(char_val);
+            if node.is_end_of_word() && best.as_ref().is_none_or(|(len, _)| beats(depth, *len)) {
+                best = Some((depth, node.display_word(path.clone())));
+            }
+            let len = path.len();
+            for (char_val, child) in node.children.iter().rev() {
+                stack.push((child, depth + 1, len, Some(*char_val)));
+            }
+        }
+        best.map(|(_, word)| word)
+    }
+
+    /// Lazily yields every stored word starting with `prefix`, in alphabetical order.
+    ///
+    /// Use this over [`words`](Self::words) when only some of the results are
+    /// needed, e.g. `trie.iter_prefix("ap").take(10)`.
+    pub fn iter_prefix(&self, prefix: &str) -> Words<'_, V> {
+        // An unknown prefix yields nothing
+        Words::new(self.normalize(prefix).and_then(|keys| self.start_at(&keys)))
+    }
+
+    /// Lazily yields every stored word in alphabetical order.
+    pub fn iter(&self) -> Words<'_, V> {
+        Words::new(Some((&self.root, String::new())))
+    }
+
+    /// Returns up to `limit` words starting with `prefix`, alphabetically smallest first.
+    ///
+    /// The traversal stops as soon as `limit` words have been found.
+    pub fn suggest(&self, prefix: &str, limit: usize) -> Vec<String> {
+        self.iter_prefix(prefix).take(limit).collect()
+    }
+
+    /// Returns the `n` most frequently inserted words starting with `prefix`,
+    /// with how many times each was inserted.
+    ///
+    /// Every insert of a word bumps its count, so this ranks completions by
+    /// popularity. Words with equal counts are ordered alphabetically.
+    pub fn most_common(&self, prefix: &str, n: usize) -> Vec<(String, u32)> {
+        let start = self.normalize(prefix).and_then(|keys| self.start_at(&keys));
+        let mut words = Words::new(start);
+        let mut ranked = Vec::new();
+        while let Some((word, node)) = words.next_node() {
+            ranked.push((word, node.count));
+        }
+        // Stable, so ties keep their alphabetical order
+        ranked.sort_by_key(|(_, count)| core::cmp::Reverse(*count));
+        ranked.truncate(n);
+        ranked
+    }
+
+    /// Returns every stored word starting with `prefix`, in the order the
+    /// words were first inserted.
+    ///
+    /// Inserting a word again doesn't move it, but removing it and inserting
+    /// it anew does. [`merge`](Self::merge) places the words it adds after
+    /// the ones already stored. The binary and serde formats don't record the
+    /// order, so loaded tries number their words alphabetically.
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let trie: Trie = ["tree", "trie", "tram", "tree"].iter().collect();
+    /// assert_eq!(trie.words("tr"), vec!["tram", "tree", "trie"]);
+    /// assert_eq!(
+    ///     trie.words_by_insertion_order("tr"),
+    ///     vec!["tree", "trie", "tram"]
+    /// );
+    /// ```
+    pub fn words_by_insertion_order(&self, prefix: &str) -> Vec<String> {
+        let start = self.normalize(prefix).and_then(|keys| self.start_at(&keys));
+        let mut words = Words::new(start);
+        let mut ordered = Vec::new();
+        while let Some((word, node)) = words.next_node() {
+            ordered.push((node.seq, word));
+        }
+        ordered.sort_unstable_by_key(|(seq, _)| *seq);
+        ordered.into_iter().map(|(_, word)| word).collect()
+    }
+
+    /// Returns up to `limit` words starting with `prefix`, each with a score
+    /// between 0 and 1, highest first.
+    ///
+    /// Frequent, short completions score highest. With `count` the number of
+    /// times a word was inserted and `len` its length in stored characters
+    /// (after normalization, whatever spelling a case-preserving trie reports
+    /// it in), both compared against the best of the completions:
+    ///
+    /// ```text
+    /// score = (count / max_count + min_len / len) / 2
+    /// ```
+    ///
+    /// Ties keep their alphabetical order. Every completion is scored before
+    /// the best are picked, so this visits the whole subtree under `prefix`;
+    /// [`complete_with_scores_by`](Self::complete_with_scores_by) takes a
+    /// scoring function of your own.
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let trie: Trie = ["car", "car", "cart", "carbon"].iter().collect();
+    /// let scores = trie.complete_with_scores("car", 2);
+    /// assert_eq!(scores[0], (String::from("car"), 1.0));
+    /// assert_eq!(scores[1].0, "cart");
+    /// ```
+    pub fn complete_with_scores(&self, prefix: &str, limit: usize) -> Vec<(String, f64)> {
+        let start = self.normalize(prefix).and_then(|keys| self.start_at(&keys));
+        let mut words = Words::new(start);
+        let mut completions = Vec::new();
+        while let Some((word, node)) = words.next_node() {
+            let len = words.path().chars().count();
+            completions.push((word, len, node.count));
+        }
+        let max_count = completions.iter().map(|(_, _, count)| *count).max();
+        let min_len = completions.iter().map(|(_, len, _)| *len).min();
+        let (Some(max_count), Some(min_len)) = (max_count, min_len) else {
+            return Vec::new();
+        };
+        let mut scored: Vec<(String, f64)> = completions
+            .into_iter()
+            .map(|(word, len, count)| {
+                let frequency = f64::from(count) / f64::from(max_count);
+                let brevity = min_len as f64 / len as f64;
+                (word, (frequency + brevity) / 2.0)
+            })
+            .collect();
+        Self::rank_scores(&mut scored, limit);
+        scored
+    }
+
+    /// Like [`complete_with_scores`](Self::complete_with_scores), but scores
+    /// each completion with `score`, given the word and how many times it
+    /// was inserted.
+    ///
+    /// The scores are used as they come, highest first, so they don't have
+    /// to lie between 0 and 1.
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let trie: Trie = ["car", "cart", "carbon"].iter().collect();
+    /// let longest = trie.complete_with_scores_by("car", 1, |word, _| word.len() as f64);
+    /// assert_eq!(longest, vec![(String::from("carbon"), 6.0)]);
+    /// ```
+    pub fn complete_with_scores_by(
+        &self,
+        prefix: &str,
+        limit: usize,
+        score: impl Fn(&str, u32) -> f64,
+    ) -> Vec<(String, f64)> {
+        let start = self.normalize(prefix).and_then(|keys| self.start_at(&keys));
+        let mut words = Words::new(start);
+        let mut scored = Vec::new();
+        while let Some((word, node)) = words.next_node() {
+            let word_score = score(&word, node.count);
+            scored.push((word, word_score));
+        }
+        Self::rank_scores(&mut scored, limit);
+        scored
+    }
+
+    // Sorts alphabetically ordered completions by descending score, keeping
+    // ties in order, and keeps the best `limit`
+    fn rank_scores(scored: &mut Vec<(String, f64)>, limit: usize) {
+        scored.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        scored.truncate(limit);
+    }
+
+    /// Returns how many stored words start with `prefix`.
+    ///
+    /// Every node keeps a count of the words in its subtree, updated by
+    /// `insert` and `remove`, so this costs O(prefix length) and doesn't
+    /// visit the matching words at all.
+    pub fn count_words_with_prefix(&self, prefix: &str) -> usize {
+        self.find_prefix(prefix).map_or(0, |node| node.word_count)
+    }
+
+    /// Returns the letters that can follow `prefix`, in alphabetical order,
+    /// or nothing if no stored word starts with `prefix`.
+    ///
+    /// See [`next_chars`](Self::next_chars) for the same letters with the
+    /// number of words behind each.
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let trie: Trie = ["apple", "apply", "ape"].iter().collect();
+    /// assert_eq!(trie.child_letters("ap"), vec!['e', 'p']);
+    /// ```
+    pub fn child_letters(&self, prefix: &str) -> Vec<char> {
+        self.find_prefix(prefix)
+            .map_or_else(Vec::new, |node| node.children.keys().copied().collect())
+    }
+
+    /// Returns the letters that can follow `prefix`, each with the number of
+    /// stored words continuing with it, most common first.
+    ///
+    /// Ties are broken alphabetically. Words equal to `prefix` itself have no
+    /// next letter and aren't counted. Like
+    /// [`count_words_with_prefix`](Self::count_words_with_prefix), this only
+    /// reads the counts kept on the children of the prefix node.
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let trie: Trie = ["car", "cat", "can", "cart"].iter().collect();
+    /// assert_eq!(trie.next_chars("ca"), vec![('r', 2), ('n', 1), ('t', 1)]);
+    /// ```
+    pub fn next_chars(&self, prefix: &str) -> Vec<(char, usize)> {
+        let Some(node) = self.find_prefix(prefix) else {
+            return Vec::new();
+        };
+        let mut next: Vec<(char, usize)> = node
+            .children
+            .iter()
+            .map(|(char_code, child)| (*char_code, child.word_count))
+            .collect();
+        // Children are already in letter order, and the sort is stable
+        next.sort_by_key(|(_, count)| core::cmp::Reverse(*count));
+        next
+    }
+
+    /// Returns how many inserts went through `prefix`, counting repeated
+    /// inserts of the same word every time.
+    ///
+    /// Where [`count_words_with_prefix`](Self::count_words_with_prefix)
+    /// counts distinct words, this sums how often each of them was inserted
+    /// (the counts reported by [`most_common`](Self::most_common)), so a word
+    /// inserted three times weighs three. It is never smaller than
+    /// `count_words_with_prefix`, and `prefix_frequency("")` is the total
+    /// number of inserts of words still stored, while [`len`](Self::len)
+    /// counts each of them once. Removing a word takes all its inserts back
+    /// out. Like the per-word counts, this saturates at `u32::MAX`.
+    pub fn prefix_frequency(&self, prefix: &str) -> u32 {
+        self.find_prefix(prefix).map_or(0, |node| node.passes)
+    }
+
+    /// Returns `true` if any stored word starts with `prefix`.
+    ///
+    /// Unlike [`words`](Self::words) this doesn't allocate. The empty prefix
+    /// matches as long as the trie holds at least one word.
+    pub fn starts_with(&self, prefix: &str) -> bool {
+        // Pruning guarantees every non-root node leads to a word
+        self.find_prefix(prefix).is_some() && !self.is_empty()
+    }
+
+    /// Returns `true` if at least one stored word starts with `prefix`,
+    /// judged by the words counted below it rather than by the path alone.
+    ///
+    /// For a trie kept pruned by its own methods this agrees with
+    /// [`starts_with`](Self::starts_with), but it stays right even if a node
+    /// were left behind with no word beneath it. Like `starts_with` it
+    /// doesn't allocate or build any words.
+    pub fn has_completions(&self, prefix: &str) -> bool {
+        self.find_prefix(prefix)
+            .is_some_and(|node| node.word_count > 0)
+    }
+
+    /// Returns the only stored word starting with `prefix`, or `None` if
+    /// there are none or several, e.g. to fill in a word on Tab.
+    ///
+    /// Every node counts the words below it, so this never looks at a
+    /// second completion: it checks the count at `prefix` and follows the
+    /// one path that leads to a word.
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let trie: Trie = ["cargo", "carpet", "cat"].iter().collect();
+    /// assert_eq!(trie.unique_completion("carp").as_deref(), Some("carpet"));
+    /// assert_eq!(trie.unique_completion("car"), None);
+    /// ```
+    pub fn unique_completion(&self, prefix: &str) -> Option<String> {
+        let keys = self.normalize(prefix)?;
+        let (mut node, mut path) = self.start_at(&keys)?;
+        if node.word_count != 1 {
+            return None;
+        }
+        while !node.is_end_of_word() {
+            let (char_code, child) = node
+                .children
+                .iter()
+                .find(|(_, child)| child.word_count > 0)?;
+            path.push(*char_code);
+            node = child;
+        }
+        Some(node.display_word(path))
+    }
+
+    /// Returns `true` if `s` leads somewhere in the trie, that is if at least
+    /// one stored word starts with it, whether or not `s` is a word itself.
+    ///
+    /// This is [`starts_with`](Self::starts_with) under a name that pairs
+    /// with [`contains`](Self::contains), which asks whether `s` is a whole
+    /// stored word. A stored word is a prefix of itself:
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let trie: Trie = ["apple"].iter().collect();
+    /// assert!(trie.is_prefix("app") && !trie.contains("app"));
+    /// assert!(trie.is_prefix("apple") && trie.contains("apple"));
+    /// ```
+    pub fn is_prefix(&self, s: &str) -> bool {
+        self.starts_with(s)
+    }
+
+    /// Returns the longest stored word that is a prefix of `query`.
+    ///
+    /// `query` is normalized the same way as inserted words.
+    pub fn longest_prefix_of(&self, query: &str) -> Option<String> {
+        let keys = self.normalize(query)?;
+        let mut current_node = &self.root;
+        let mut path = String::new();
+        let mut longest = current_node
+            .is_end_of_word()
+            .then(|| current_node.display_word(String::new()));
+        for char_code in keys {
+            match current_node.children.get(&char_code) {
+                Some(node) => current_node = node,
+                None => break, // No stored word goes any deeper
+            }
+            path.push(char_code);
+            if current_node.is_end_of_word() {
+                longest = Some(current_node.display_word(path.clone()));
+            }
+        }
+        longest
+    }
+
+    /// Returns every stored word that is a prefix of `query`, shortest
+    /// first.
+    ///
+    /// The last one, if any, is [`longest_prefix_of`](Self::longest_prefix_of).
+    /// `query` is normalized the same way as inserted words, and a single
+    /// walk down the trie finds all of them.
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let trie: Trie = ["a", "ap", "app", "apple", "b"].iter().collect();
+    /// assert_eq!(trie.prefixes_of("apples"), vec!["a", "ap", "app", "apple"]);
+    /// ```
+    pub fn prefixes_of(&self, query: &str) -> Vec<String> {
+        let Some(keys) = self.normalize(query) else {
+            return Vec::new();
+        };
+        let mut current_node = &self.root;
+        let mut path = String::new();
+        let mut prefixes = Vec::new();
+        if current_node.is_end_of_word() {
+            prefixes.push(current_node.display_word(String::new()));
+        }
+        for char_code in keys {
+            match current_node.children.get(&char_code) {
+                Some(node) => current_node = node,
+                None => break, // No stored word goes any deeper
+            }
+            path.push(char_code);
+            if current_node.is_end_of_word() {
+                prefixes.push(current_node.display_word(path.clone()));
+            }
+        }
+        prefixes
+    }
+
+    /// Returns the longest prefix of `query` that some stored word starts
+    /// with, that is how far down the trie `query` can be followed.
+    ///
+    /// Unlike [`longest_prefix_of`](Self::longest_prefix_of), the prefix
+    /// doesn't have to be a stored word itself. It is returned normalized,
+    /// and is empty when not even the first character matches.
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let trie: Trie = ["apple"].iter().collect();
+    /// assert_eq!(trie.common_prefix_with("application"), "appl");
+    /// ```
+    pub fn common_prefix_with(&self, query: &str) -> String {
+        let mut current_node = &self.root;
+        let mut path = String::new();
+        for char_code in self.normalize(query).unwrap_or_default() {
+            match current_node.children.get(&char_code) {
+                Some(node) => current_node = node,
+                None => break,
+            }
+            path.push(char_code);
+        }
+        path
+    }
+
+    /// Returns every `k` such that `s[..k]` is a stored word, in increasing
+    /// order.
+    ///
+    /// Lengths are byte offsets into `s` itself, so they can be used to slice
+    /// it directly, e.g. when segmenting text into dictionary words. A single
+    /// walk down the trie finds all of them.
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let trie: Trie = ["a", "aa", "aaa"].iter().collect();
+    /// assert_eq!(trie.prefix_word_lengths("aaab"), vec![1, 2, 3]);
+    /// ```
+    pub fn prefix_word_lengths(&self, s: &str) -> Vec<usize> {
+        let mut lengths = Vec::new();
+        let mut current_node = &self.root;
+        let mut buf = [0; 4];
+        for (i, char_val) in s.char_indices() {
+            // Normalizing one character at a time keeps track of where each
+            // key came from in `s`
+            let Some(keys) = self.normalize(char_val.encode_utf8(&mut buf)) else {
+                break; // Rejected, so no longer prefix can be a stored word
+            };
+            for char_code in keys {
+                match current_node.children.get(&char_code) {
+                    Some(node) => current_node = node,
+                    None => return lengths,
+                }
+            }
+            if current_node.is_end_of_word() {
+                lengths.push(i + char_val.len_utf8());
+            }
+        }
+        lengths
+    }
+
+    /// Returns the longest prefix shared by every stored word.
+    ///
+    /// An empty trie has no words to share anything, so it returns `""`.
+    pub fn longest_common_prefix(&self) -> String {
+        let mut prefix = String::new();
+        let mut current_node = &self.root;
+        // Stop where the words branch apart or one of them ends
+        while !current_node.is_end_of_word() && current_node.children.len() == 1 {
+            let (char_code, child) = current_node.children.iter().next().unwrap();
+            prefix.push(*char_code);
+            current_node = child;
+        }
+        prefix
+    }
+
+    /// Returns the shortest prefix of `word` that no other stored word starts with.
+    ///
+    /// This relies on the per-node word counts: the answer ends at the first
+    /// node that only one word passes through. Counts are per distinct word,
+    /// so inserting the same word again doesn't change the result. If `word`
+    /// is itself a prefix of other words, the whole word is returned; if it's
+    /// the only word stored, even `""` is unique. Returns `None` if `word`
+    /// isn't stored.
+    pub fn shortest_unique_prefix(&self, word: &str) -> Option<String> {
+        let keys = self.normalize(word)?;
+        self.find_keys(&keys)?.value.as_ref()?;
+        let mut prefix = String::new();
+        let mut current_node = &self.root;
+        for char_code in keys {
+            if current_node.word_count == 1 {
+                break;
+            }
+            prefix.push(char_code);
+            current_node = &current_node.children[&char_code];
+        }
+        Some(prefix)
+    }
+
+    /// Returns `true` if some stored word matches `pattern`, where `.` stands
+    /// for exactly one character.
+    ///
+    /// Apart from `.`, the pattern is normalized the same way as inserted words.
+    pub fn search_pattern(&self, pattern: &str) -> bool {
+        // `None` marks a wildcard position
+        let mut keys: Vec<Option<char>> = Vec::new();
+        for (i, piece) in pattern.split('.').enumerate() {
+            if i > 0 {
+                keys.push(None);
+            }
+            let Some(piece_keys) = self.normalize(piece) else {
+                return false;
+            };
+            keys.extend(piece_keys.into_iter().map(Some));
+        }
+        Self::matches_pattern(&self.root, &keys)
+    }
+
+    // Depth-first over (node, position in the pattern) pairs, with an
+    // explicit stack since a pattern can be as long as the longest word
+    fn matches_pattern(node: &TrieNode<V>, pattern: &[Option<char>]) -> bool {
+        let mut stack = vec![(node, 0)];
+        while let Some((node, i)) = stack.pop() {
+            match pattern.get(i) {
+                None => {
+                    if node.is_end_of_word() {
+                        return true;
+                    }
+                }
+                // Any child will do, as long as the rest of the pattern
+                // matches below it
+                Some(None) => stack.extend(node.children.values().map(|child| (child, i + 1))),
+                Some(Some(char_code)) => {
+                    stack.extend(node.children.get(char_code).map(|child| (child, i + 1)));
+                }
+            }
+        }
+        false
+    }
+
+    // Walks down the path spelled by `prefix` (or a whole word), after
+    // normalizing it
+    fn find_prefix(&self, prefix: &str) -> Option<&TrieNode<V>> {
+        self.find_keys(&self.normalize(prefix)?)
+    }
+
+    // Where to start listing the words under `keys`: their node, and the
+    // path to it spelled from the normalized keys, never from the raw input,
+    // which may hold characters that were skipped on the way down
+    fn start_at(&self, keys: &[char]) -> Option<(&TrieNode<V>, String)> {
+        Some((self.find_keys(keys)?, keys.iter().collect()))
+    }
+
+    fn find_keys(&self, keys: &[char]) -> Option<&TrieNode<V>> {
+        let mut current_node = &self.root;
+        for char_code in keys {
+            current_node = current_node.children.get(char_code)?;
+        }
+        Some(current_node)
+    }
+
+    /// Removes a word, returning `true` if it was present.
+    ///
+    /// Nodes that no longer lead to any word are pruned.
+    pub fn remove(&mut self, word: &str) -> bool {
+        let Some(keys) = self.normalize_word(word) else {
+            return false;
+        };
+        let removed = Self::remove_from(&mut self.root, &keys).is_some();
+        if removed {
+            self.len -= 1;
+        }
+        removed
+    }
+
+    /// Removes every word, leaving the trie as if freshly created.
+    pub fn clear(&mut self) {
+        self.root = TrieNode::new();
+        self.len = 0;
+        self.next_seq = 0;
+    }
+
+    /// Removes every word starting with `prefix` and returns them in alphabetical order.
+    ///
+    /// The whole subtree under the prefix is detached in one go, so this is
+    /// much cheaper than removing its words one at a time. Words that only
+    /// share part of the prefix are left alone, and an unknown prefix
+    /// removes nothing.
+    pub fn drain_prefix(&mut self, prefix: &str) -> Vec<String> {
+        let Some(keys) = self.normalize(prefix) else {
+            return Vec::new();
+        };
+        let Some(subtree) = self.detach(&keys) else {
+            return Vec::new();
+        };
+        Words::new(Some((&*subtree, keys.into_iter().collect()))).collect()
+    }
+
+    /// Removes every word starting with `prefix`, returning how many were
+    /// removed.
+    ///
+    /// Like [`drain_prefix`](Self::drain_prefix), but without building the
+    /// removed words: the count is read off the detached subtree. This is the
+    /// bulk-delete counterpart of
+    /// [`count_words_with_prefix`](Self::count_words_with_prefix).
+    pub fn remove_prefix(&mut self, prefix: &str) -> usize {
+        self.normalize(prefix)
+            .and_then(|keys| self.detach(&keys))
+            .map_or(0, |subtree| subtree.word_count)
+    }
+
+    /// Removes every word starting with `prefix` and returns them as a
+    /// separate trie, keyed by what follows the prefix.
+    ///
+    /// Splitting `"car"` off a trie holding `"cart"` and `"carbon"` returns
+    /// a trie of `"t"` and `"bon"`; [`split_off_with_prefix`] keeps whole
+    /// words instead. A word equal to `prefix` would have an empty suffix,
+    /// which can't be stored, so it stays in `self`. Values, insert counts
+    /// and the configuration carry over, but case-preserved spellings don't:
+    /// they spell the whole word, so the suffixes are reported normalized.
+    ///
+    /// [`split_off_with_prefix`]: Self::split_off_with_prefix
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let mut trie: Trie = ["car", "cart", "carbon", "cat"].iter().collect();
+    /// let suffixes = trie.split_off("car");
+    /// assert_eq!(suffixes.words(""), vec!["bon", "t"]);
+    /// assert_eq!(trie.words(""), vec!["car", "cat"]);
+    /// ```
+    pub fn split_off(&mut self, prefix: &str) -> Trie<V> {
+        let mut split = self.empty_like();
+        let Some(keys) = self.normalize(prefix) else {
+            return split;
+        };
+        let Some(mut subtree) = self.detach(&keys) else {
+            return split;
+        };
+        if let Some(value) = subtree.value.take() {
+            // Hand the prefix word itself back to `self`
+            let count = core::mem::take(&mut subtree.count);
+            subtree.word_count -= 1;
+            subtree.passes = subtree.passes.saturating_sub(count);
+            let node = self.attach_path(&keys, 1, count);
+            node.value = Some(value);
+            node.count = count;
+            node.original = subtree.original.take();
+            node.seq = subtree.seq;
+            self.len += 1;
+        }
+        let mut stack = vec![&mut *subtree];
+        while let Some(node) = stack.pop() {
+            node.original = None;
+            stack.extend(node.children.values_mut());
+        }
+        split.len = subtree.word_count;
+        split.root = *subtree;
+        split
+    }
+
+    /// Like [`split_off`](Self::split_off), but the returned trie keeps the
+    /// whole words, prefix included, so nothing stays behind in `self`.
+    ///
+    /// Together, the two tries hold exactly the words `self` held before.
+    pub fn split_off_with_prefix(&mut self, prefix: &str) -> Trie<V> {
+        let mut split = self.empty_like();
+        let Some(keys) = self.normalize(prefix) else {
+            return split;
+        };
+        let Some(subtree) = self.detach(&keys) else {
+            return split;
+        };
+        split.len = subtree.word_count;
+        // Rebuild the path above the subtree, bottom up
+        let mut node = *subtree;
+        for char_code in keys.iter().rev() {
+            let mut parent = TrieNode::new();
+            parent.word_count = node.word_count;
+            parent.passes = node.passes;
+            parent.children.insert(*char_code, Box::new(node));
+            node = parent;
+        }
+        split.root = node;
+        split
+    }
+
+    // An empty trie with the same configuration
+    fn empty_like<W>(&self) -> Trie<W> {
+        Trie {
+            root: TrieNode::new(),
+            len: 0,
+            next_seq: self.next_seq,
+            preserve_case: self.preserve_case,
+            case_sensitive: self.case_sensitive,
+            alphabet: self.alphabet,
+            #[cfg(feature = "fold-diacritics")]
+            fold_diacritics: self.fold_diacritics,
+            non_alpha_policy: self.non_alpha_policy,
+            max_word_length: self.max_word_length,
+            length_policy: self.length_policy,
+        }
+    }
+
+    // Walks down `keys`, creating missing nodes, and adds `words` and
+    // `passes` to every node on the way, for a subtree being put back
+    fn attach_path(&mut self, keys: &[char], words: usize, passes: u32) -> &mut TrieNode<V> {
+        let mut current_node = &mut self.root;
+        current_node.word_count += words;
+        current_node.passes = current_node.passes.saturating_add(passes);
+        for char_code in keys {
+            current_node = current_node
+                .children
+                .get_or_insert_with(*char_code, TrieNode::new);
+            current_node.word_count += words;
+            current_node.passes = current_node.passes.saturating_add(passes);
+        }
+        current_node
+    }
+
+    /// Keeps only the words for which `f` returns `true`, pruning any branch
+    /// left without words.
+    ///
+    /// `f` sees each word the way [`words`](Self::words) reports it.
+    pub fn retain(&mut self, mut f: impl FnMut(&str) -> bool) {
+        self.retain_words(|word, _| f(word));
+    }
+
+    // Removes the words for which `keep`, given the word as reported and its
+    // insert count, returns `false`, and returns how many there were.
+    //
+    // A post-order walk with an explicit stack, so long words can't overflow
+    // it. Each node's children are taken out while they're visited and the
+    // ones still leading to a word put back, with their counts brought up to
+    // date, on the way back up.
+    fn retain_words(&mut self, mut keep: impl FnMut(&str, u32) -> bool) -> usize {
+        let mut path = String::new();
+        let mut root = Box::new(core::mem::replace(&mut self.root, TrieNode::new()));
+        let removed = Self::remove_word_unless(&mut root, &path, &mut keep);
+        let children = root.take_children();
+        let mut stack = vec![(None, root, children, removed)];
+        loop {
+            let (_, _, children, _) = stack.last_mut().unwrap();
+            if let Some((char_code, mut child)) = children.next() {
+                path.push(char_code);
+                let removed = Self::remove_word_unless(&mut child, &path, &mut keep);
+                let children = child.take_children();
+                stack.push((Some(char_code), child, children, removed));
+                continue;
+            }
+            let (char_code, mut node, _, removed) = stack.pop().unwrap();
+            node.word_count -= removed;
+            node.recount_passes();
+            match (stack.last_mut(), char_code) {
+                (Some((_, parent, _, parent_removed)), Some(char_code)) => {
+                    path.pop();
+                    *parent_removed += removed;
+                    if !node.is_prunable() {
+                        parent.children.insert(char_code, node);
+                    }
+                }
+                _ => {
+                    self.root = *node;
+                    self.len -= removed;
+                    return removed;
+                }
+            }
+        }
+    }
+
+    // Clears the word ending at `node` unless `keep` says otherwise,
+    // returning 1 if it was removed
+    fn remove_word_unless(
+        node: &mut TrieNode<V>,
+        path: &str,
+        keep: &mut impl FnMut(&str, u32) -> bool,
+    ) -> usize {
+        if !node.is_end_of_word() || keep(node.original.as_deref().unwrap_or(path), node.count) {
+            return 0;
+        }
+        node.value = None;
+        node.count = 0;
+        node.original = None;
+        1
+    }
+
+    /// Removes every word inserted fewer than `min_count` times, pruning any
+    /// branch left without words, and returns how many were removed.
+    ///
+    /// The counts are the ones [`most_common`](Self::most_common) reports,
+    /// so this clears out typos and other one-offs from a trie built from a
+    /// corpus. Shared prefixes are kept as long as a surviving word uses
+    /// them.
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let mut trie: Trie = ["the", "the", "teh", "cat", "cat"].iter().collect();
+    /// assert_eq!(trie.trim_rare(2), 1);
+    /// assert_eq!(trie.words(""), vec!["cat", "the"]);
+    /// ```
+    pub fn trim_rare(&mut self, min_count: u32) -> usize {
+        self.retain_words(|_, count| count >= min_count)
+    }
+
+    // Cuts off the subtree at the end of `keys`, keeping counts up to date
+    // and pruning ancestors that no longer lead to any word
+    fn detach(&mut self, keys: &[char]) -> Option<Box<TrieNode<V>>> {
+        let subtree = if keys.is_empty() {
+            Box::new(core::mem::replace(&mut self.root, TrieNode::new()))
+        } else {
+            Self::detach_from(&mut self.root, keys)?
+        };
+        self.len -= subtree.word_count;
+        Some(subtree)
+    }
+
+    // Walks down once, taking the subtree's counts off every ancestor. The
+    // first child that leads to nothing else is cut off whole, and the
+    // subtree dug out of it.
+    fn detach_from(node: &mut TrieNode<V>, keys: &[char]) -> Option<Box<TrieNode<V>>> {
+        let (last, path) = keys.split_last()?;
+        let mut target = &*node;
+        for key in keys {
+            target = target.children.get(key)?;
+        }
+        let (words, passes) = (target.word_count, target.passes);
+        let mut current_node = node;
+        for (i, key) in path.iter().enumerate() {
+            current_node.word_count -= words;
+            current_node.passes = current_node.passes.saturating_sub(passes);
+            if current_node.children.get(key).unwrap().word_count == words {
+                let mut chain = current_node.children.remove(key).unwrap();
+                let mut chain_node = &mut *chain;
+                for key in &path[i + 1..] {
+                    chain_node = chain_node.children.get_mut(key).unwrap();
+                }
+                return chain_node.children.remove(last);
+            }
+            current_node = current_node.children.get_mut(key).unwrap();
+        }
+        current_node.word_count -= words;
+        current_node.passes = current_node.passes.saturating_sub(passes);
+        current_node.children.remove(last)
+    }
+
+    // Returns how many times the removed word had been inserted, or `None`
+    // if it wasn't stored. The first node on the way down that leads to no
+    // other word is cut off along with everything below it.
+    fn remove_from(node: &mut TrieNode<V>, keys: &[char]) -> Option<u32> {
+        // Look before touching any counts; a prefix without a value isn't a
+        // stored word
+        let mut target = &*node;
+        for key in keys {
+            target = target.children.get(key)?;
+        }
+        target.value.as_ref()?;
+        let count = target.count;
+        let mut current_node = node;
+        for key in keys {
+            current_node.word_count -= 1;
+            current_node.passes = current_node.passes.saturating_sub(count);
+            if current_node.children.get(key).unwrap().word_count == 1 {
+                current_node.children.remove(key);
+                return Some(count);
+            }
+            current_node = current_node.children.get_mut(key).unwrap();
+        }
+        current_node.value = None;
+        current_node.count = 0;
+        current_node.word_count -= 1;
+        current_node.passes = current_node.passes.saturating_sub(count);
+        current_node.original = None;
+        Some(count)
+    }
+}
+
+impl<V: Clone> Trie<V> {
+    /// Adds every word of `other` to `self`, leaving `self` with the union of both.
+    ///
+    /// The tries are walked together node by node: subtrees missing from
+    /// `self` are copied over whole, and shared prefixes are only visited
+    /// once. Where both tries store a word, the value from `other` wins.
+    ///
+    /// Words new to `self` count as inserted after all of its own, in the
+    /// order they were inserted into `other`.
+    pub fn merge(&mut self, other: &Trie<V>) {
+        self.merge_with(other, false);
+    }
+
+    /// Like [`merge`](Self::merge), but adds up the insert counts of words
+    /// stored in both, as reported by [`most_common`](Self::most_common), so
+    /// frequency tries built from separate shards combine into one.
+    ///
+    /// Words only in `other` keep their counts, and the sums saturate at
+    /// `u32::MAX` like the counts themselves.
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let mut total: Trie = ["the", "the", "cat"].iter().collect();
+    /// let shard: Trie = ["the", "dog"].iter().collect();
+    /// total.merge_weighted(&shard);
+    /// assert_eq!(total.most_common("", 1), vec![(String::from("the"), 3)]);
+    /// assert_eq!(total.total_inserts(), 5);
+    /// ```
+    pub fn merge_weighted(&mut self, other: &Trie<V>) {
+        self.merge_with(other, true);
+    }
+
+    // Walks the two tries together with an explicit stack, so long words
+    // can't overflow it. Each shared node is taken out of its parent while
+    // its children are merged and put back with its counts brought up to
+    // date, which is when the number of words new below it is known.
+    fn merge_with(&mut self, other: &Trie<V>, sum_counts: bool) {
+        let seq_offset = self.next_seq;
+        let mut root = Box::new(core::mem::replace(&mut self.root, TrieNode::new()));
+        let added = Self::merge_word(&mut root, &other.root, seq_offset, sum_counts);
+        let mut stack = vec![(None, root, other.root.children.iter(), added)];
+        loop {
+            let (_, target, source_children, added) = stack.last_mut().unwrap();
+            if let Some((char_code, source_child)) = source_children.next() {
+                match target.children.remove(char_code) {
+                    Some(mut target_child) => {
+                        let added = Self::merge_word(
+                            &mut target_child,
+                            source_child,
+                            seq_offset,
+                            sum_counts,
+                        );
+                        let children = source_child.children.iter();
+                        stack.push((Some(*char_code), target_child, children, added));
+                    }
+                    None => {
+                        *added += source_child.word_count;
+                        let mut child = Box::new(source_child.clone());
+                        let mut nodes = vec![&mut *child];
+                        while let Some(node) = nodes.pop() {
+                            node.seq += seq_offset;
+                            nodes.extend(node.children.values_mut());
+                        }
+                        target.children.insert(*char_code, child);
+                    }
+                }
+                continue;
+            }
+            let (char_code, mut node, _, added) = stack.pop().unwrap();
+            node.word_count += added;
+            node.recount_passes();
+            match (stack.last_mut(), char_code) {
+                (Some((_, parent, _, parent_added)), Some(char_code)) => {
+                    *parent_added += added;
+                    parent.children.insert(char_code, node);
+                }
+                _ => {
+                    self.root = *node;
+                    self.len += added;
+                    break;
+                }
+            }
+        }
+        self.next_seq += other.next_seq;
+    }
+
+    // Merges the word ending at `source`, if any, into `target`, returning
+    // 1 if it was new. Its sequence number is shifted by `seq_offset`, past
+    // the target trie's own.
+    fn merge_word(
+        target: &mut TrieNode<V>,
+        source: &TrieNode<V>,
+        seq_offset: u64,
+        sum_counts: bool,
+    ) -> usize {
+        let mut added = 0;
+        if let Some(value) = &source.value {
+            if target.value.replace(value.clone()).is_none() {
+                added += 1;
+                target.count = source.count;
+                target.seq = source.seq + seq_offset;
+            } else if sum_counts {
+                target.count = target.count.saturating_add(source.count);
+            }
+            if source.original.is_some() {
+                target.original.clone_from(&source.original);
+            }
+        }
+        added
+    }
+}
+
+impl<V: Clone> Trie<V> {
+    /// Returns the words stored in both `self` and `other`, with their values
+    /// and counts from `self`.
+    ///
+    /// Like [`merge`](Self::merge), this walks the tries together, so only
+    /// prefixes the two share are visited. Words are matched as stored, after
+    /// normalization, and the result is configured like `self`.
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let a: Trie = ["apple", "ape", "ball"].iter().collect();
+    /// let b: Trie = ["ape", "ball", "cat"].iter().collect();
+    /// assert_eq!(a.intersection(&b).words(""), vec!["ape", "ball"]);
+    /// assert_eq!(a.difference(&b).words(""), vec!["apple"]);
+    /// ```
+    pub fn intersection<W>(&self, other: &Trie<W>) -> Trie<V> {
+        self.combine(other, true)
+    }
+
+    /// Returns the words stored in `self` but not in `other`, with their
+    /// values and counts from `self`.
+    ///
+    /// Subtrees `other` doesn't reach are copied over whole, without walking
+    /// them. See [`intersection`](Self::intersection).
+    pub fn difference<W>(&self, other: &Trie<W>) -> Trie<V> {
+        self.combine(other, false)
+    }
+
+    // Builds the intersection or the difference with an explicit stack, so
+    // long words can't overflow it. A node is attached to its parent once all
+    // its children are, unless nothing was kept below it.
+    fn combine<W>(&self, other: &Trie<W>, intersect: bool) -> Trie<V> {
+        let start = Self::combined_word(&self.root, &other.root, intersect);
+        let mut stack = vec![(None, start, self.root.children.iter(), &other.root)];
+        loop {
+            let (_, result, children, other_node) = stack.last_mut().unwrap();
+            if let Some((char_code, child)) = children.next() {
+                match other_node.children.get(char_code) {
+                    Some(other_child) => {
+                        let node = Self::combined_word(child, other_child, intersect);
+                        stack.push((Some(*char_code), node, child.children.iter(), other_child));
+                    }
+                    // Subtrees `other` doesn't reach are all kept or all dropped
+                    None if !intersect => {
+                        result.word_count += child.word_count;
+                        result.children.insert(*char_code, Box::new(child.clone()));
+                    }
+                    None => {}
+                }
+                continue;
+            }
+            let (char_code, mut node, _, _) = stack.pop().unwrap();
+            node.recount_passes();
+            match (stack.last_mut(), char_code) {
+                (Some((_, parent, _, _)), Some(char_code)) => {
+                    if !node.is_prunable() {
+                        parent.word_count += node.word_count;
+                        parent.children.insert(char_code, Box::new(node));
+                    }
+                }
+                _ => {
+                    let mut combined = self.empty_like();
+                    combined.len = node.word_count;
+                    combined.root = node;
+                    return combined;
+                }
+            }
+        }
+    }
+
+    // A fresh node holding the word ending at `node`, if the intersection or
+    // difference keeps it
+    fn combined_word<W>(node: &TrieNode<V>, other: &TrieNode<W>, intersect: bool) -> TrieNode<V> {
+        let mut result = TrieNode::new();
+        if other.is_end_of_word() == intersect {
+            result.copy_word_from(node);
+        }
+        result
+    }
+}
+
+impl<V> Trie<V> {
+    /// Returns `true` if every word stored in `self` is also stored in
+    /// `other`.
+    ///
+    /// The tries are walked together and the walk stops at the first word
+    /// `other` lacks, without listing either trie's words. Words are
+    /// compared as stored, after normalization; values are ignored.
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let vocabulary: Trie = ["the", "cat", "sat"].iter().collect();
+    /// let document: Trie = ["the", "cat"].iter().collect();
+    /// assert!(document.is_subset_of(&vocabulary));
+    /// assert!(!vocabulary.is_subset_of(&document));
+    /// ```
+    pub fn is_subset_of<W>(&self, other: &Trie<W>) -> bool {
+        let mut stack = vec![(&self.root, &other.root)];
+        while let Some((node, other_node)) = stack.pop() {
+            // Fewer words below `other_node` means one of ours is missing
+            if node.word_count > other_node.word_count
+                || (node.is_end_of_word() && !other_node.is_end_of_word())
+            {
+                return false;
+            }
+            for (char_code, child) in node.children.iter() {
+                match other_node.children.get(char_code) {
+                    Some(other_child) => stack.push((child, other_child)),
+                    None => return false,
+                }
+            }
+        }
+        true
+    }
+}
+
+impl<V: Clone> TrieNode<V> {
+    // Copies the word ending at `node`, if any, into this fresh node
+    fn copy_word_from(&mut self, node: &TrieNode<V>) {
+        if node.is_end_of_word() {
+            self.value.clone_from(&node.value);
+            self.count = node.count;
+            self.original.clone_from(&node.original);
+            self.seq = node.seq;
+            self.word_count += 1;
+        }
+    }
+}
+
+impl<V: Clone> TrieNode<V> {
+    // A copy of this node alone, with room for its children
+    fn clone_without_children(&self) -> Self {
+        TrieNode {
+            children: Children::with_capacity(self.children.len()),
+            value: self.value.clone(),
+            word_count: self.word_count,
+            count: self.count,
+            passes: self.passes,
+            original: self.original.clone(),
+            seq: self.seq,
+        }
+    }
+}
+
+// Cloned with an explicit stack for the same reason as `Drop` below. Each
+// copy is attached to its parent once all of its own children are.
+impl<V: Clone> Clone for TrieNode<V> {
+    fn clone(&self) -> Self {
+        let mut stack = vec![(self.children.iter(), self.clone_without_children(), None)];
+        loop {
+            let (children, _, _) = stack.last_mut().unwrap();
+            if let Some((char_code, child)) = children.next() {
+                let copy = child.clone_without_children();
+                stack.push((child.children.iter(), copy, Some(*char_code)));
+                continue;
+            }
+            let (_, node, char_code) = stack.pop().unwrap();
+            match (stack.last_mut(), char_code) {
+                (Some((_, parent, _)), Some(char_code)) => {
+                    parent.children.insert(char_code, Box::new(node));
+                }
+                _ => return node,
+            }
+        }
+    }
+}
+
+// Dropping children one level at a time would recurse once per character of
+// the longest word, so long words are torn down with an explicit stack instead
+impl<V> Drop for TrieNode<V> {
+    fn drop(&mut self) {
+        let mut stack: Vec<Box<TrieNode<V>>> =
+            core::mem::take(&mut self.children).into_values().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.extend(core::mem::take(&mut node.children).into_values());
+        }
+    }
+}
+
+// Two nodes are equal when they hold the same words and values below them.
+// Counters are derived from that, and the original spellings are cosmetic.
+// Pairs of nodes go on an explicit stack so long words can't overflow it.
+impl<V: PartialEq> PartialEq for TrieNode<V> {
+    fn eq(&self, other: &Self) -> bool {
+        let mut stack = vec![(self, other)];
+        while let Some((node, other_node)) = stack.pop() {
+            if node.value != other_node.value
+                || node.children.len() != other_node.children.len()
+                || !node.children.keys().eq(other_node.children.keys())
+            {
+                return false;
+            }
+            stack.extend(node.children.values().zip(other_node.children.values()));
+        }
+        true
+    }
+}
+
+impl<V: Eq> Eq for TrieNode<V> {}
+
+/// Tries are equal when they store the same words (with equal values),
+/// regardless of insertion order or configuration.
+impl<V: PartialEq> PartialEq for Trie<V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.root == other.root
+    }
+}
+
+impl<V: Eq> Eq for Trie<V> {}
+
+/// Hashes the words and values, like [`PartialEq`] compares them, so equal
+/// tries hash the same whatever order their words were inserted in.
+impl<V: Hash> Hash for Trie<V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+        // Each node's value and letters, in preorder, fix the whole shape.
+        // An explicit stack keeps long words from recursing.
+        let mut stack = vec![&self.root];
+        while let Some(node) = stack.pop() {
+            node.value.hash(state);
+            node.children.len().hash(state);
+            for char_code in node.children.keys() {
+                char_code.hash(state);
+            }
+            stack.extend(node.children.values().rev());
+        }
+    }
+}
+
+impl<'a, V> IntoIterator for &'a Trie<V> {
+    type Item = String;
+    type IntoIter = Words<'a, V>;
+
+    fn into_iter(self) -> Words<'a, V> {
+        self.iter()
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for Trie {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        let mut trie = Trie::new();
+        trie.extend(iter);
+        trie
+    }
+}
+
+impl<S: AsRef<str>> Extend<S> for Trie {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, iter: I) {
+        self.insert_all(iter);
+    }
+}
+
+/// Lists the stored words in alphabetical order, one per line. An empty
+/// trie prints nothing.
+impl<V> fmt::Display for Trie<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, word) in self.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            f.write_str(&word)?;
+        }
+        Ok(())
+    }
+}
+
+// Implement Debug for TrieNode
+impl<V> fmt::Debug for TrieNode<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug_map = f.debug_map();
+        for char_val in self.children.keys() {
+            // We don't want to recursively print the whole tree here
+            debug_map.entry(char_val, &"Some(TrieNode)"); // Indicate child exists
+        }
+        debug_map.finish()?;
+        write!(f, ", is_end_of_word: {}", self.is_end_of_word())
+    }
+}
+
+// test cases
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Runs the structural checks, which only exist with debug assertions
+    #[cfg_attr(not(debug_assertions), allow(unused_variables))]
+    fn assert_invariants<V>(trie: &Trie<V>) {
+        #[cfg(debug_assertions)]
+        if let Err(problem) = trie.check_invariants() {
+            panic!("{problem}");
+        }
+    }
+
+    #[test]
+    fn test_insert_and_contains() {
+        let mut trie = Trie::new();
+        trie.insert("hello");
+        assert!(trie.contains("hello"));
+        assert!(!trie.contains("hell"));
+        trie.insert("hell");
+        assert!(trie.contains("hell"));
+    }
+
+    #[test]
+    fn test_case_insensitivity() {
+        let mut trie = Trie::new();
+        trie.insert("Hello");
+        assert!(trie.contains("hello"));
+        assert!(trie.contains("HELLO"));
+    }
+
+    #[test]
+    fn test_non_alphabetic_characters() {
+        let mut trie = Trie::new();
+        trie.insert("apple!");
+        assert!(trie.contains("apple"));
+        assert!(trie.contains("apple%"));
+        assert!(!trie.contains("app%"));
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut trie = Trie::new();
+        trie.insert("hell");
+        trie.insert("hello");
+        assert!(trie.remove("hell"));
+        assert!(!trie.contains("hell"));
+        assert!(trie.contains("hello"));
+        assert!(!trie.remove("hell"));
+        assert_invariants(&trie);
+    }
+
+    #[test]
+    fn test_remove_missing_or_prefix() {
+        let mut trie = Trie::new();
+        trie.insert("hello");
+        assert!(!trie.remove("help"));
+        assert!(!trie.remove("hel"));
+        assert!(!trie.remove("helloo"));
+        assert!(trie.contains("hello"));
+        assert_eq!(trie.words("hel"), vec!["hello"]);
+    }
+
+    #[test]
+    fn test_remove_very_long_word() {
+        // Deep enough to overflow the stack with a recursive removal
+        let word = "ab".repeat(25_000);
+        let mut trie = Trie::new();
+        trie.insert(&word);
+        trie.insert(&format!("{word}c"));
+        trie.insert("abc");
+        assert!(trie.remove(&format!("{word}c")));
+        assert!(trie.contains(&word));
+        assert!(!trie.remove("ab"));
+        assert!(trie.remove(&word));
+        assert_eq!(trie.words(""), vec!["abc"]);
+        assert_eq!(trie.node_count(), 4);
+        assert_eq!(trie.prefix_frequency("ab"), 1);
+    }
+
+    #[test]
+    fn test_remove_prunes_nodes() {
+        let mut trie = Trie::new();
+        trie.insert("hello");
+        trie.insert("help");
+        assert!(trie.remove("hello"));
+        // Only the "hel" + "p" branch should be left
+        let hel = trie.find_prefix("hel").unwrap();
+        assert!(hel.children.get(&'l').is_none());
+        assert!(hel.children.get(&'p').is_some());
+        assert!(trie.remove("help"));
+        assert!(trie.root.is_prunable());
+        assert_invariants(&trie);
+    }
+
+    #[test]
+    fn test_len_and_is_empty() {
+        let mut trie = Trie::new();
+        assert!(trie.is_empty());
+        assert_eq!(trie.len(), 0);
+        trie.insert("cat");
+        trie.insert("Cat");
+        trie.insert("car");
+        assert_eq!(trie.len(), 2);
+        assert!(!trie.is_empty());
+        trie.remove("cat");
+        assert_eq!(trie.len(), 1);
+        trie.remove("cat");
+        assert_eq!(trie.len(), 1);
+    }
+
+    #[test]
+    fn test_empty_input_is_not_a_word() {
+        let mut trie = Trie::new();
+        assert!(!trie.insert("!!!"));
+        assert!(!trie.insert(""));
+        assert_eq!(trie.len(), 0);
+        assert!(!trie.contains(""));
+        assert!(trie.words("").is_empty());
+        assert!(!trie.remove(""));
+        assert!(!trie.starts_with(""));
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut trie = Trie::new();
+        trie.insert("apple");
+        trie.insert("ball");
+        trie.insert("!!!");
+        trie.clear();
+        assert!(!trie.contains("apple"));
+        assert!(!trie.contains(""));
+        assert!(trie.words("").is_empty());
+        assert!(trie.is_empty());
+        trie.insert("cat");
+        assert_eq!(trie.words(""), vec!["cat"]);
+        assert_invariants(&trie);
+    }
+
+    #[test]
+    fn test_starts_with() {
+        let mut trie = Trie::new();
+        assert!(!trie.starts_with(""));
+        trie.insert("apple");
+        assert!(trie.starts_with(""));
+        assert!(trie.starts_with("app"));
+        assert!(trie.starts_with("Ap-p"));
+        assert!(trie.starts_with("apple"));
+        assert!(!trie.starts_with("apples"));
+        assert!(!trie.starts_with("b"));
+    }
+
+    #[test]
+    fn test_unique_completion() {
+        let mut trie = Trie::preserving_case();
+        for word in ["Carpet", "cargo", "cat", "dog", "do"] {
+            trie.insert(word);
+        }
+        assert_eq!(trie.unique_completion("carp").as_deref(), Some("Carpet"));
+        assert_eq!(trie.unique_completion("CARG").as_deref(), Some("cargo"));
+        assert_eq!(trie.unique_completion("cat").as_deref(), Some("cat"));
+        assert_eq!(trie.unique_completion("dog").as_deref(), Some("dog"));
+        // Several completions, or none
+        assert_eq!(trie.unique_completion("car"), None);
+        assert_eq!(trie.unique_completion("do"), None);
+        assert_eq!(trie.unique_completion(""), None);
+        assert_eq!(trie.unique_completion("cow"), None);
+        let one: Trie = ["alone"].iter().collect();
+        assert_eq!(one.unique_completion("").as_deref(), Some("alone"));
+        assert_eq!(Trie::new().unique_completion(""), None);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn test_check_invariants_reports_corruption() {
+        let mut trie: Trie = ["car", "cart", "cat"].iter().collect();
+        assert_eq!(trie.check_invariants(), Ok(()));
+        assert_eq!(Trie::new().check_invariants(), Ok(()));
+        trie.len = 2;
+        assert_eq!(
+            trie.check_invariants(),
+            Err(String::from("len is 2, but 3 words are stored"))
+        );
+        trie.len = 3;
+        fn car(trie: &mut Trie) -> &mut TrieNode<()> {
+            let c = trie.root.children.get_mut(&'c').unwrap();
+            let ca = c.children.get_mut(&'a').unwrap();
+            ca.children.get_mut(&'r').unwrap()
+        }
+        // A miscount shows up against the parent first
+        car(&mut trie).word_count = 3;
+        assert_eq!(
+            trie.check_invariants(),
+            Err(String::from(
+                "node \"ca\": counts 3 words, but 4 end at or below it"
+            ))
+        );
+        car(&mut trie).word_count = 2;
+        car(&mut trie).passes += 1;
+        assert_eq!(
+            trie.check_invariants(),
+            Err(String::from(
+                "node \"ca\": counts 3 inserts, but 4 went through it"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_has_completions() {
+        let mut trie = Trie::new();
+        assert!(!trie.has_completions(""));
+        trie.insert("apple");
+        trie.insert("ape");
+        assert!(trie.has_completions(""));
+        assert!(trie.has_completions("AP"));
+        assert!(trie.has_completions("apple"));
+        assert!(!trie.has_completions("apples"));
+        trie.remove("apple");
+        assert!(!trie.has_completions("app"));
+        // A branch with no word in it, which pruning normally prevents
+        trie.root
+            .children
+            .get_mut(&'a')
+            .unwrap()
+            .children
+            .insert('x', Box::new(TrieNode::new()));
+        assert!(trie.starts_with("ax"));
+        assert!(!trie.has_completions("ax"));
+        assert!(trie.has_completions("a"));
+        #[cfg(debug_assertions)]
+        assert_eq!(
+            trie.check_invariants(),
+            Err(String::from("node \"ax\": leads to no word"))
+        );
+    }
+
+    #[test]
+    fn test_unicode_words() {
+        let mut trie = Trie::new();
+        trie.insert("café");
+        trie.insert("Naïve");
+        trie.insert("route66");
+        trie.insert("日本語");
+        assert!(trie.contains("café"));
+        assert!(!trie.contains("caf"));
+        assert!(trie.contains("NAÏVE"));
+        assert!(trie.contains("route66"));
+        assert!(trie.contains("日本語"));
+        assert!(trie.starts_with("日本"));
+        assert_eq!(trie.words("caf"), vec!["café"]);
+    }
+
+    #[test]
+    fn test_words_sorted_with_unicode() {
+        let mut trie = Trie::new();
+        trie.insert("cafe");
+        trie.insert("café");
+        trie.insert("cafa");
+        trie.insert("caf1");
+        assert_eq!(trie.words("caf"), vec!["caf1", "cafa", "cafe", "café"]);
+    }
+
+    #[test]
+    fn test_insert_value_and_get() {
+        let mut trie: Trie<u32> = Trie::default();
+        assert_eq!(trie.insert_value("apple", 1), None);
+        assert_eq!(trie.insert_value("Apple", 2), Some(1));
+        trie.insert_value("ape", 3);
+        assert_eq!(trie.get("apple"), Some(&2));
+        assert_eq!(trie.get("ap"), None);
+        assert!(trie.contains("ape"));
+        assert_eq!(trie.len(), 2);
+        assert_eq!(trie.words("ap"), vec!["ape", "apple"]);
+    }
+
+    #[test]
+    fn test_get_mut() {
+        let mut trie: Trie<u32> = Trie::default();
+        trie.insert_value("count", 0);
+        *trie.get_mut("count").unwrap() += 5;
+        assert_eq!(trie.get("count"), Some(&5));
+        assert!(trie.get_mut("missing").is_none());
+        assert!(trie.get_mut("count!").is_some());
+    }
+
+    #[test]
+    fn test_suggest() {
+        let mut trie = Trie::new();
+        for word in ["card", "care", "car", "cat", "cab", "dog"] {
+            trie.insert(word);
+        }
+        assert_eq!(trie.suggest("ca", 3), vec!["cab", "car", "card"]);
+        assert_eq!(trie.suggest("car", 10), vec!["car", "card", "care"]);
+        assert!(trie.suggest("ca", 0).is_empty());
+        assert!(trie.suggest("x", 5).is_empty());
+    }
+
+    #[test]
+    fn test_from_iterator() {
+        let trie: Trie = ["apple", "Ape", "ball!"].iter().collect();
+        assert_eq!(trie.words(""), vec!["ape", "apple", "ball"]);
+
+        let lines = "one\ntwo\nthree".lines().map(String::from);
+        let trie = Trie::from_iter(lines);
+        assert_eq!(trie.len(), 3);
+        assert!(trie.contains("two"));
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut trie: Trie = vec!["cat"].into_iter().collect();
+        trie.extend(vec![String::from("car"), String::from("cat")]);
+        assert_eq!(trie.words(""), vec!["car", "cat"]);
+    }
+
+    #[test]
+    fn test_longest_prefix_of() {
+        let mut trie = Trie::new();
+        trie.insert("ap");
+        trie.insert("apple");
+        assert_eq!(
+            trie.longest_prefix_of("applesauce").as_deref(),
+            Some("apple")
+        );
+        assert_eq!(trie.longest_prefix_of("ap").as_deref(), Some("ap"));
+        assert_eq!(trie.longest_prefix_of("appl").as_deref(), Some("ap"));
+        assert_eq!(trie.longest_prefix_of("App-le!").as_deref(), Some("apple"));
+        assert_eq!(trie.longest_prefix_of("a"), None);
+        assert_eq!(trie.longest_prefix_of("banana"), None);
+    }
+
+    #[test]
+    fn test_common_prefix_with() {
+        let trie: Trie = ["apple", "apply", "banana"].iter().collect();
+        assert_eq!(trie.common_prefix_with("application"), "appl");
+        assert_eq!(trie.common_prefix_with("Apple"), "apple");
+        assert_eq!(trie.common_prefix_with("applesauce"), "apple");
+        assert_eq!(trie.common_prefix_with("ban-ner"), "ban");
+        assert_eq!(trie.common_prefix_with("cherry"), "");
+        assert_eq!(trie.common_prefix_with(""), "");
+        assert_eq!(Trie::new().common_prefix_with("apple"), "");
+    }
+
+    #[test]
+    fn test_prefixes_of() {
+        let trie: Trie = ["a", "ap", "app", "apple", "banana"].iter().collect();
+        assert_eq!(trie.prefixes_of("apple"), vec!["a", "ap", "app", "apple"]);
+        // Longer than any stored word
+        assert_eq!(
+            trie.prefixes_of("Applesauce!"),
+            vec!["a", "ap", "app", "apple"]
+        );
+        assert_eq!(trie.prefixes_of("apl"), vec!["a", "ap"]);
+        // Shorter than every word on its branch
+        assert!(trie.prefixes_of("ban").is_empty());
+        assert!(trie.prefixes_of("").is_empty());
+        assert!(trie.prefixes_of("cherry").is_empty());
+    }
+
+    #[test]
+    fn test_search_pattern() {
+        let mut trie = Trie::new();
+        trie.insert("apple");
+        trie.insert("bad");
+        trie.insert("dad");
+        assert!(trie.search_pattern("a.ple"));
+        assert!(trie.search_pattern(".ad"));
+        assert!(trie.search_pattern("b.."));
+        assert!(trie.search_pattern("bad"));
+        assert!(!trie.search_pattern("a.pl"));
+        assert!(!trie.search_pattern("c.."));
+    }
+
+    #[test]
+    fn test_search_pattern_lengths() {
+        let mut trie = Trie::new();
+        trie.insert("apple");
+        trie.insert("bad");
+        assert!(trie.search_pattern("..."));
+        assert!(trie.search_pattern("....."));
+        assert!(!trie.search_pattern("...."));
+        assert!(!trie.search_pattern("......"));
+        assert!(!trie.search_pattern(""));
+    }
+
+    #[test]
+    fn test_search_pattern_very_long_word() {
+        // Deep enough to overflow the stack with a recursive match
+        let word = "ab".repeat(25_000);
+        let mut trie: Trie = [word.as_str(), "abc"].iter().collect();
+        trie.insert(&word.replace('b', "c"));
+        assert!(trie.search_pattern(&word.replace('b', ".")));
+        assert!(trie.search_pattern(&".".repeat(50_000)));
+        assert!(!trie.search_pattern(&".".repeat(49_999)));
+        assert!(!trie.search_pattern(&word.replace('a', ".").replace('b', "d")));
+    }
+
+    #[test]
+    fn test_count_words_with_prefix() {
+        let mut trie = Trie::new();
+        for word in ["undo", "unzip", "under", "under", "up", "apple"] {
+            trie.insert(word);
+        }
+        assert_eq!(trie.count_words_with_prefix("un"), 3);
+        assert_eq!(trie.count_words_with_prefix("u"), 4);
+        assert_eq!(trie.count_words_with_prefix(""), 5);
+        assert_eq!(trie.count_words_with_prefix("under"), 1);
+        assert_eq!(trie.count_words_with_prefix("x"), 0);
+        trie.remove("undo");
+        trie.remove("missing");
+        trie.remove("un");
+        assert_eq!(trie.count_words_with_prefix("un"), 2);
+        assert_eq!(trie.count_words_with_prefix(""), trie.len());
+    }
+
+    #[test]
+    fn test_longest_common_prefix() {
+        let trie: Trie = ["flower", "flow", "flight"].iter().collect();
+        assert_eq!(trie.longest_common_prefix(), "fl");
+        let trie: Trie = ["flower", "flow"].iter().collect();
+        assert_eq!(trie.longest_common_prefix(), "flow");
+        let trie: Trie = ["dog", "cat"].iter().collect();
+        assert_eq!(trie.longest_common_prefix(), "");
+        let trie: Trie = ["single"].iter().collect();
+        assert_eq!(trie.longest_common_prefix(), "single");
+        assert_eq!(Trie::new().longest_common_prefix(), "");
+    }
+
+    #[test]
+    fn test_shortest_unique_prefix() {
+        let trie: Trie = ["dog", "dove", "duck"].iter().collect();
+        assert_eq!(trie.shortest_unique_prefix("dove").as_deref(), Some("dov"));
+        assert_eq!(trie.shortest_unique_prefix("duck").as_deref(), Some("du"));
+        assert_eq!(trie.shortest_unique_prefix("Dog").as_deref(), Some("dog"));
+        assert_eq!(trie.shortest_unique_prefix("do"), None);
+        assert_eq!(trie.shortest_unique_prefix("cat"), None);
+    }
+
+    #[test]
+    fn test_shortest_unique_prefix_edge_cases() {
+        let mut trie: Trie = ["do", "dog", "zebra"].iter().collect();
+        assert_eq!(trie.shortest_unique_prefix("do").as_deref(), Some("do"));
+        assert_eq!(trie.shortest_unique_prefix("zebra").as_deref(), Some("z"));
+        // Repeated inserts don't make a word look less unique
+        trie.insert("zebra");
+        assert_eq!(trie.shortest_unique_prefix("zebra").as_deref(), Some("z"));
+        let trie: Trie = ["only"].iter().collect();
+        assert_eq!(trie.shortest_unique_prefix("only").as_deref(), Some(""));
+    }
+
+    #[test]
+    fn test_preserving_case() {
+        let mut trie = Trie::preserving_case();
+        trie.insert("Apple");
+        trie.insert("apply");
+        trie.insert("IBM");
+        assert!(trie.contains("APPLE"));
+        assert!(trie.contains("ibm"));
+        assert_eq!(trie.words("Ap"), vec!["Apple", "apply"]);
+        assert_eq!(trie.suggest("i", 1), vec!["IBM"]);
+        assert_eq!(
+            trie.longest_prefix_of("applesauce").as_deref(),
+            Some("Apple")
+        );
+        // Re-inserting updates the spelling without adding a word
+        trie.insert("APPLE");
+        assert_eq!(trie.words("apple"), vec!["APPLE"]);
+        assert_eq!(trie.len(), 3);
+    }
+
+    #[test]
+    fn test_default_trie_lowercases_words() {
+        let mut trie = Trie::new();
+        trie.insert("Apple");
+        assert_eq!(trie.words(""), vec!["apple"]);
+    }
+
+    #[test]
+    fn test_contains_matches_insert_normalization() {
+        // Regression: contains used to reject input that insert accepted
+        let mut trie = Trie::new();
+        trie.insert("apple!");
+        assert!(trie.contains("apple!"));
+        assert!(trie.contains("apple"));
+        trie.insert("ape'");
+        assert!(trie.contains("ape'"));
+        assert_eq!(trie.shortest_unique_prefix("a-p-e").as_deref(), Some("ape"));
+    }
+
+    #[test]
+    fn test_node_count_and_height() {
+        let mut trie = Trie::new();
+        assert_eq!(trie.node_count(), 1);
+        assert_eq!(trie.height(), 0);
+        trie.insert("car");
+        trie.insert("cart");
+        trie.insert("cat");
+        // root, c, a, r, t (after r), t (after a)
+        assert_eq!(trie.node_count(), 6);
+        assert_eq!(trie.height(), 4);
+        trie.remove("cart");
+        assert_eq!(trie.node_count(), 5);
+        assert_eq!(trie.height(), 3);
+    }
+
+    #[test]
+    fn test_clone_is_independent() {
+        let mut trie: Trie = ["apple", "ape", "ball"].iter().collect();
+        let snapshot = trie.clone();
+        assert_eq!(snapshot.words(""), trie.words(""));
+        assert_eq!(snapshot.len(), trie.len());
+        assert!(snapshot.contains("ape"));
+
+        trie.insert("cat");
+        trie.remove("apple");
+        assert!(!snapshot.contains("cat"));
+        assert!(snapshot.contains("apple"));
+        assert_eq!(snapshot.len(), 3);
+        assert_eq!(snapshot.count_words_with_prefix("ap"), 2);
+    }
+
+    #[test]
+    fn test_clone_very_long_word() {
+        // Deep enough to overflow the stack with a recursive clone
+        let word = "ab".repeat(25_000);
+        let mut trie = Trie::preserving_case();
+        trie.insert(&word.to_uppercase());
+        trie.insert("abc");
+        let copy = trie.clone();
+        assert_eq!(copy.words(""), trie.words(""));
+        assert_eq!(copy.height(), 50_000);
+        assert_eq!(copy.prefix_frequency("ab"), 2);
+        // Subtrees new to the target are cloned whole
+        let mut merged = Trie::new();
+        merged.merge(&trie);
+        assert_eq!(merged.words(""), trie.words(""));
+        assert_eq!(trie.difference(&Trie::<()>::new()).len(), 2);
+    }
+
+    #[test]
+    fn test_eq_ignores_insert_order() {
+        let forward: Trie = ["a", "b", "apple"].iter().collect();
+        let backward: Trie = ["apple", "b", "a"].iter().collect();
+        assert_eq!(forward, backward);
+
+        let mut other: Trie = ["a", "b"].iter().collect();
+        assert_ne!(forward, other);
+        other.insert("apple");
+        assert_eq!(forward, other);
+        other.insert("app");
+        other.remove("app");
+        assert_eq!(forward, other);
+        assert_eq!(Trie::new(), Trie::new());
+    }
+
+    #[test]
+    fn test_eq_very_long_word() {
+        // Deep enough to overflow the stack with a recursive comparison
+        let word = "ab".repeat(25_000);
+        let mut left = Trie::new();
+        left.insert(&word);
+        left.insert("abc");
+        let mut right = Trie::new();
+        right.insert("abc");
+        right.insert(&word);
+        assert_eq!(left, right);
+        right.insert(&format!("{word}c"));
+        right.remove(&word);
+        assert_ne!(left, right);
+    }
+
+    #[test]
+    fn test_eq_compares_values() {
+        let mut left: Trie<u32> = Trie::default();
+        let mut right: Trie<u32> = Trie::default();
+        left.insert_value("key", 1);
+        right.insert_value("key", 2);
+        assert_ne!(left, right);
+        right.insert_value("key", 1);
+        assert_eq!(left, right);
+    }
+
+    #[test]
+    fn test_hash_matches_eq() {
+        use std::{
+            collections::HashMap,
+            hash::{BuildHasher, RandomState},
+        };
+
+        let hasher = RandomState::new();
+        let forward: Trie = ["a", "b", "apple", "ab"].iter().collect();
+        let mut backward: Trie = ["ab", "apple", "B", "a", "a"].iter().collect();
+        assert_eq!(hasher.hash_one(&forward), hasher.hash_one(&backward));
+        backward.insert("app");
+        assert_ne!(hasher.hash_one(&forward), hasher.hash_one(&backward));
+        backward.remove("app");
+        assert_eq!(hasher.hash_one(&forward), hasher.hash_one(&backward));
+        // Same letters, different words
+        let split: Trie = ["ab", "c"].iter().collect();
+        let joined: Trie = ["abc"].iter().collect();
+        assert_ne!(hasher.hash_one(&split), hasher.hash_one(&joined));
+        let mut values = Trie::<u32>::default();
+        values.insert_value("key", 1);
+        let mut other = Trie::<u32>::default();
+        other.insert_value("key", 2);
+        assert_ne!(hasher.hash_one(&values), hasher.hash_one(&other));
+
+        let mut cache = HashMap::new();
+        cache.insert(forward, "cached");
+        assert_eq!(cache.get(&backward), Some(&"cached"));
+        assert_eq!(cache.get(&split), None);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut left: Trie = ["apple", "ape", "ball"].iter().collect();
+        let right: Trie = ["ape", "apex", "cat", "b"].iter().collect();
+        left.merge(&right);
+        // "ape" is in both, so the union has six words
+        assert_eq!(left.len(), 6);
+        assert_eq!(
+            left.words(""),
+            vec!["ape", "apex", "apple", "b", "ball", "cat"]
+        );
+        for word in ["apple", "ball", "apex", "cat", "b"] {
+            assert!(left.contains(word));
+        }
+        assert_eq!(left.count_words_with_prefix("ap"), 3);
+        assert_eq!(right.len(), 4);
+        assert_invariants(&left);
+    }
+
+    #[test]
+    fn test_merge_very_long_word() {
+        // Deep enough to overflow the stack with a recursive merge
+        let word = "ab".repeat(25_000);
+        let mut left = Trie::new();
+        left.insert(&word);
+        left.insert("abc");
+        let mut right = Trie::new();
+        right.insert(&word);
+        right.insert(&format!("{word}c"));
+        left.merge_weighted(&right);
+        assert_eq!(left.len(), 3);
+        assert_eq!(left.count_words_with_prefix(&word), 2);
+        assert_eq!(left.prefix_frequency(&word), 3);
+        assert_eq!(left.most_common(&word, 1), vec![(word.clone(), 2)]);
+        assert_eq!(left.prefix_frequency(""), 4);
+    }
+
+    #[test]
+    fn test_merge_values() {
+        let mut left: Trie<u32> = Trie::default();
+        left.insert_value("one", 1);
+        left.insert_value("two", 0);
+        let mut right: Trie<u32> = Trie::default();
+        right.insert_value("two", 2);
+        left.merge(&right);
+        assert_eq!(left.get("one"), Some(&1));
+        assert_eq!(left.get("two"), Some(&2));
+        assert_eq!(left.len(), 2);
+    }
+
+    #[test]
+    fn test_merge_weighted() {
+        let shards = [
+            ["the", "the", "cat", "sat", "the"],
+            ["the", "dog", "dog", "sat", "then"],
+        ];
+        let mut total = Trie::new();
+        for shard in &shards {
+            total.merge_weighted(&shard.iter().collect());
+        }
+        let mut expected = Trie::new();
+        for word in shards.iter().flatten() {
+            expected.insert(word);
+        }
+        assert_eq!(total, expected);
+        assert_eq!(total.most_common("", 10), expected.most_common("", 10));
+        assert_eq!(total.most_common("the", 1), vec![(String::from("the"), 4)]);
+        assert_eq!(total.prefix_frequency("th"), 5);
+        assert_eq!(total.total_inserts(), 10);
+        // A plain merge keeps the counts already in `self`
+        let mut plain: Trie = shards[0].iter().collect();
+        plain.merge(&shards[1].iter().collect());
+        assert_eq!(plain.most_common("the", 1), vec![(String::from("the"), 3)]);
+        assert_invariants(&total);
+        assert_invariants(&plain);
+    }
+
+    #[test]
+    fn test_most_common() {
+        let mut trie = Trie::new();
+        for word in [
+            "the", "they", "then", "they", "then", "they", "this", "apple",
+        ] {
+            trie.insert(word);
+        }
+        assert_eq!(
+            trie.most_common("th", 3),
+            vec![
+                (String::from("they"), 3),
+                (String::from("then"), 2),
+                (String::from("the"), 1)
+            ]
+        );
+        assert_eq!(trie.most_common("th", 10).len(), 4);
+        assert_eq!(trie.most_common("th", 10)[3], (String::from("this"), 1));
+        assert!(trie.most_common("th", 0).is_empty());
+        assert!(trie.most_common("x", 3).is_empty());
+    }
+
+    #[test]
+    fn test_most_common_after_remove() {
+        let mut trie = Trie::new();
+        trie.insert("cat");
+        trie.insert("cat");
+        trie.remove("cat");
+        trie.insert("cat");
+        trie.insert("car");
+        trie.insert("car");
+        assert_eq!(
+            trie.most_common("", 2),
+            vec![(String::from("car"), 2), (String::from("cat"), 1)]
+        );
+    }
+
+    #[test]
+    fn test_words_by_insertion_order() {
+        let mut trie = Trie::new();
+        for word in ["zebra", "apple", "mango", "Apple", "ape", "banana"] {
+            trie.insert(word);
+        }
+        assert_eq!(
+            trie.words(""),
+            vec!["ape", "apple", "banana", "mango", "zebra"]
+        );
+        assert_eq!(
+            trie.words_by_insertion_order(""),
+            vec!["zebra", "apple", "mango", "ape", "banana"]
+        );
+        assert_eq!(trie.words_by_insertion_order("ap"), vec!["apple", "ape"]);
+        assert!(trie.words_by_insertion_order("x").is_empty());
+        // Only reinserting after a remove moves a word to the back
+        trie.remove("zebra");
+        trie.insert("zebra");
+        *trie.entry("kiwi").unwrap().or_default() = ();
+        assert_eq!(
+            trie.words_by_insertion_order(""),
+            vec!["apple", "mango", "ape", "banana", "zebra", "kiwi"]
+        );
+        trie.clear();
+        trie.insert("b");
+        trie.insert("a");
+        assert_eq!(trie.words_by_insertion_order(""), vec!["b", "a"]);
+        assert_invariants(&trie);
+    }
+
+    #[test]
+    fn test_insertion_order_across_merge_and_from_sorted() {
+        let trie = Trie::from_sorted(&["b", "a", "c", "a"]);
+        assert_eq!(trie.words_by_insertion_order(""), vec!["b", "a", "c"]);
+        let mut merged: Trie = ["pear", "fig"].iter().collect();
+        let other: Trie = ["plum", "fig", "date", "peach"].iter().collect();
+        merged.merge(&other);
+        assert_eq!(
+            merged.words_by_insertion_order(""),
+            vec!["pear", "fig", "plum", "date", "peach"]
+        );
+        merged.insert("lime");
+        assert_eq!(merged.words_by_insertion_order("l"), vec!["lime"]);
+        assert_eq!(merged.words_by_insertion_order("").last().unwrap(), "lime");
+        let mut truncated: Trie = ["cart", "dog", "car", "cab"].iter().collect();
+        truncated.truncate_depth(2);
+        assert_eq!(truncated.words_by_insertion_order(""), vec!["ca", "do"]);
+        assert_invariants(&merged);
+        assert_invariants(&truncated);
+    }
+
+    #[test]
+    fn test_trim_rare_very_long_word() {
+        // Deep enough to overflow the stack with a recursive walk
+        let word = "ab".repeat(25_000);
+        let mut trie = Trie::new();
+        trie.insert_with_count(&word, 3);
+        trie.insert(&format!("{word}c"));
+        assert_eq!(trie.trim_rare(2), 1);
+        assert_eq!(trie.words(""), vec![word.clone()]);
+        assert_eq!(trie.prefix_frequency(""), 3);
+        assert_eq!(trie.node_count(), 50_001);
+    }
+
+    #[test]
+    fn test_trim_rare() {
+        let mut trie = Trie::new();
+        for (word, count) in [("car", 3), ("cart", 1), ("care", 2), ("cat", 1), ("dog", 5)] {
+            for _ in 0..count {
+                trie.insert(word);
+            }
+        }
+        assert_eq!(trie.trim_rare(1), 0);
+        assert_eq!(trie.trim_rare(2), 2);
+        assert_eq!(trie.words(""), vec!["car", "care", "dog"]);
+        assert_eq!(trie.len(), 3);
+        assert_eq!(trie.count_words_with_prefix("ca"), 2);
+        assert_eq!(trie.prefix_frequency("ca"), 5);
+        // "cat" and "cart" leave no nodes behind
+        assert_eq!(trie.node_count(), 8);
+        assert_eq!(trie.trim_rare(u32::MAX), 3);
+        assert!(trie.is_empty());
+        assert_eq!(trie.node_count(), 1);
+        assert_invariants(&trie);
+    }
+
+    #[test]
+    fn test_complete_with_scores() {
+        let mut trie = Trie::new();
+        for (word, count) in [("tea", 1), ("team", 4), ("tease", 2), ("ten", 1), ("t", 1)] {
+            for _ in 0..count {
+                trie.insert(word);
+            }
+        }
+        let scores = trie.complete_with_scores("te", 10);
+        let expected = [
+            ("team", (4.0 / 4.0 + 3.0 / 4.0) / 2.0),
+            ("tea", (1.0 / 4.0 + 3.0 / 3.0) / 2.0),
+            ("ten", (1.0 / 4.0 + 3.0 / 3.0) / 2.0),
+            ("tease", (2.0 / 4.0 + 3.0 / 5.0) / 2.0),
+        ];
+        assert_eq!(scores.len(), expected.len());
+        for ((word, score), (expected_word, expected_score)) in scores.iter().zip(expected) {
+            assert_eq!(word, expected_word);
+            assert!((score - expected_score).abs() < 1e-12);
+        }
+        assert!(scores.iter().all(|(_, score)| (0.0..=1.0).contains(score)));
+        assert_eq!(trie.complete_with_scores("te", 1)[0].0, "team");
+        assert!(trie.complete_with_scores("x", 3).is_empty());
+        assert!(trie.complete_with_scores("te", 0).is_empty());
+    }
+
+    #[test]
+    fn test_complete_with_scores_uses_stored_length() {
+        // "can't" is stored as four letters, even though it's reported as
+        // five characters
+        let mut trie = Trie::preserving_case();
+        trie.insert("can't");
+        trie.insert("Canto");
+        assert_eq!(
+            trie.complete_with_scores("can", 2),
+            vec![
+                (String::from("can't"), 1.0),
+                (String::from("Canto"), (1.0 + 4.0 / 5.0) / 2.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_complete_with_scores_by() {
+        let trie: Trie = ["tea", "team", "team", "ten"].iter().collect();
+        let by_count = trie.complete_with_scores_by("te", 2, |_, count| f64::from(count));
+        assert_eq!(
+            by_count,
+            vec![(String::from("team"), 2.0), (String::from("tea"), 1.0)]
+        );
+        let negative = trie.complete_with_scores_by("", 5, |word, _| -(word.len() as f64));
+        assert_eq!(negative[0], (String::from("tea"), -3.0));
+        assert_eq!(negative[2], (String::from("team"), -4.0));
+    }
+
+    #[test]
+    fn test_insert_reports_new_words() {
+        let mut trie = Trie::new();
+        assert!(trie.insert("cat"));
+        assert!(!trie.insert("cat"));
+        assert!(!trie.insert("CAT!"));
+        assert!(trie.insert("cats"));
+        assert_eq!(trie.len(), 2);
+    }
+
+    #[test]
+    fn test_drain_prefix() {
+        let mut trie: Trie = ["car", "card", "care", "cat", "ca", "dog"].iter().collect();
+        assert_eq!(trie.drain_prefix("car"), vec!["car", "card", "care"]);
+        assert_eq!(trie.words(""), vec!["ca", "cat", "dog"]);
+        assert_eq!(trie.len(), 3);
+        assert_eq!(trie.count_words_with_prefix("c"), 2);
+        assert!(!trie.starts_with("car"));
+    }
+
+    #[test]
+    fn test_drain_prefix_prunes_and_handles_missing() {
+        let mut trie: Trie = ["apple", "dog"].iter().collect();
+        assert!(trie.drain_prefix("b").is_empty());
+        assert!(trie.drain_prefix("apples").is_empty());
+        assert_eq!(trie.len(), 2);
+        assert_eq!(trie.drain_prefix("A-pp"), vec!["apple"]);
+        // Nothing is left of the "a" branch
+        assert_eq!(trie.node_count(), 4);
+        assert_eq!(trie.drain_prefix(""), vec!["dog"]);
+        assert!(trie.is_empty());
+        assert_eq!(trie.node_count(), 1);
+    }
+
+    #[test]
+    fn test_retain_very_long_word() {
+        // Deep enough to overflow the stack with a recursive walk
+        let word = "ab".repeat(25_000);
+        let mut trie = Trie::new();
+        trie.insert(&word);
+        trie.insert(&format!("{word}c"));
+        trie.insert("abc");
+        trie.retain(|kept| kept.len() != word.len() + 1);
+        assert_eq!(trie.words(""), vec![word.clone(), String::from("abc")]);
+        assert_eq!(trie.node_count(), 50_002);
+        trie.retain(|_| false);
+        assert!(trie.is_empty());
+        assert_eq!(trie.node_count(), 1);
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut trie: Trie = ["a", "tree", "trie", "tries", "tr", "bark", "barks"]
+            .iter()
+            .collect();
+        trie.retain(|word| word.chars().count() == 4);
+        assert_eq!(trie.words(""), vec!["bark", "tree", "trie"]);
+        assert_eq!(trie.len(), 3);
+        assert!(trie.contains("trie"));
+        assert!(!trie.contains("tries"));
+        assert!(!trie.contains("a"));
+        assert_eq!(trie.count_words_with_prefix("tr"), 2);
+        // Root, "bark", the shared "tr" and the "ee"/"ie" endings
+        assert_eq!(trie.node_count(), 1 + 4 + 2 + 2 + 2);
+        assert_invariants(&trie);
+    }
+
+    #[test]
+    fn test_all_words() {
+        let trie: Trie = ["apple", "ape", "ball"].iter().collect();
+        assert_eq!(trie.all_words(), vec!["ape", "apple", "ball"]);
+        assert_eq!(trie.all_words(), trie.words(""));
+        assert!(Trie::new().all_words().is_empty());
+    }
+
+    #[test]
+    fn test_words_of_length() {
+        let trie: Trie = ["tea", "ten", "team", "ate", "a", "Café"].iter().collect();
+        assert_eq!(trie.words_of_length(3), vec!["ate", "tea", "ten"]);
+        assert_eq!(trie.words_of_length(4), vec!["café", "team"]);
+        assert_eq!(trie.words_of_length(1), vec!["a"]);
+        assert!(trie.words_of_length(2).is_empty());
+        assert!(trie.words_of_length(10).is_empty());
+        assert!(trie.words_of_length(0).is_empty());
+    }
+
+    #[test]
+    fn test_prefix_word_lengths() {
+        let trie: Trie = ["a", "aa", "aaa", "café", "cafés"].iter().collect();
+        assert_eq!(trie.prefix_word_lengths("aaab"), vec![1, 2, 3]);
+        assert_eq!(trie.prefix_word_lengths("aa"), vec![1, 2]);
+        assert!(trie.prefix_word_lengths("baa").is_empty());
+        assert!(trie.prefix_word_lengths("").is_empty());
+        // Offsets are in bytes of the input, skipped characters included
+        let s = "Café-s!";
+        let lengths = trie.prefix_word_lengths(s);
+        assert_eq!(lengths, vec![5, 6, 7, 8]);
+        assert!(lengths.iter().all(|k| trie.contains(&s[..*k])));
+        let strict = trie.clone().with_non_alpha_policy(NonAlphaPolicy::Reject);
+        assert_eq!(strict.prefix_word_lengths("a-aa"), vec![1]);
+    }
+
+    #[test]
+    fn test_display_lists_words() {
+        let trie: Trie = ["ball", "apple", "ape"].iter().collect();
+        assert_eq!(trie.to_string(), "ape\napple\nball");
+        assert_eq!(Trie::new().to_string(), "");
+        let mut trie = Trie::preserving_case();
+        trie.insert("NASA");
+        assert_eq!(format!("{trie}"), "NASA");
+    }
+
+    #[test]
+    fn test_completions_suffixes() {
+        let words = ["apple", "ape", "apex", "ap", "bat", "Apt"];
+        let trie: Trie = words.iter().collect();
+        assert_eq!(
+            trie.completions_suffixes("ap"),
+            vec!["", "e", "ex", "ple", "t"]
+        );
+        for prefix in ["", "a", "ap", "A-P", "ape", "b"] {
+            let normalized: String = trie.normalize(prefix).unwrap().iter().collect();
+            let rebuilt: Vec<String> = trie
+                .completions_suffixes(prefix)
+                .iter()
+                .map(|suffix| format!("{normalized}{suffix}"))
+                .collect();
+            assert_eq!(rebuilt, trie.words(prefix));
+        }
+        assert!(trie.completions_suffixes("apples").is_empty());
+        assert!(Trie::new().completions_suffixes("").is_empty());
+        let mut cased = Trie::preserving_case();
+        cased.insert("NASA");
+        assert_eq!(cased.completions_suffixes("na"), vec!["sa"]);
+    }
+
+    #[test]
+    fn test_insert_with_count() {
+        let mut weighted = Trie::new();
+        assert!(weighted.insert_with_count("a", 1000));
+        assert!(weighted.insert_with_count("ab", 40));
+        assert!(!weighted.insert_with_count("A", 2));
+        assert!(weighted.insert_with_count("b", 1));
+        let mut repeated = Trie::new();
+        for (word, n) in [("a", 1002), ("ab", 40), ("b", 1)] {
+            for _ in 0..n {
+                repeated.insert(word);
+            }
+        }
+        assert_eq!(weighted.most_common("", 3), repeated.most_common("", 3));
+        assert_eq!(weighted.prefix_frequency("a"), 1042);
+        assert_eq!(weighted.total_inserts(), repeated.total_inserts());
+        assert_eq!(weighted.count_words_with_prefix(""), 3);
+        assert_eq!(weighted, repeated);
+        assert!(!weighted.insert_with_count("c", 0));
+        assert!(!weighted.contains("c"));
+        assert!(!weighted.insert_with_count("!", 5));
+        weighted.insert_with_count("b", u32::MAX);
+        assert_eq!(weighted.most_common("b", 1)[0].1, u32::MAX);
+        assert_invariants(&weighted);
+    }
+
+    #[test]
+    fn test_insert_all_counts_new_words() {
+        let mut trie = Trie::new();
+        assert_eq!(trie.insert_all(["apple", "ape", "Apple", "!!"]), 2);
+        assert_eq!(trie.insert_all(["ape", "ball"].iter()), 1);
+        assert_eq!(trie.insert_all(Vec::<String>::new()), 0);
+        assert_eq!(trie.len(), 3);
+    }
+
+    #[test]
+    fn test_remove_prefix() {
+        let mut trie: Trie = ["car", "cart", "carbon", "cat", "dog"].iter().collect();
+        let before = trie.clone();
+        assert_eq!(trie.remove_prefix("cb"), 0);
+        assert_eq!(trie.remove_prefix("cars"), 0);
+        assert_eq!(trie, before);
+        assert_eq!(trie.remove_prefix("Car"), 3);
+        assert_eq!(trie.words(""), vec!["cat", "dog"]);
+        assert_eq!(trie.len(), 2);
+        assert_eq!(trie.count_words_with_prefix("c"), 1);
+        assert_eq!(trie.remove_prefix(""), 2);
+        assert!(trie.is_empty());
+        assert_eq!(trie.node_count(), 1);
+        assert_invariants(&trie);
+    }
+
+    #[test]
+    fn test_remove_prefix_very_long_word() {
+        // Deep enough to overflow the stack with a recursive prefix walk
+        let word = "ab".repeat(25_000);
+        let mut trie: Trie = [word.as_str(), "abc"].iter().collect();
+        assert_eq!(trie.remove_prefix(&word[..40_000]), 1);
+        assert_eq!(trie.words(""), vec!["abc"]);
+        assert_eq!(trie.node_count(), 4);
+        trie.insert(&word);
+        assert_eq!(trie.drain_prefix(&word), vec![word.clone()]);
+        assert_eq!(trie.words(""), vec!["abc"]);
+        trie.insert(&word);
+        let split = trie.split_off(&word[..3]);
+        assert_eq!(split.len(), 1);
+        assert_eq!(trie.words(""), vec!["abc"]);
+        assert_eq!(trie.node_count(), 4);
+    }
+
+    #[test]
+    fn test_longest_and_shortest_word() {
+        let trie: Trie = ["banana", "kiwi", "fig", "apple", "cherry", "ant"]
+            .iter()
+            .collect();
+        assert_eq!(trie.longest_word().as_deref(), Some("banana"));
+        assert_eq!(trie.shortest_word().as_deref(), Some("ant"));
+        let trie: Trie = ["ab", "abcd", "abc"].iter().collect();
+        assert_eq!(trie.longest_word().as_deref(), Some("abcd"));
+        assert_eq!(trie.shortest_word().as_deref(), Some("ab"));
+        assert_eq!(Trie::new().longest_word(), None);
+        assert_eq!(Trie::new().shortest_word(), None);
+    }
+
+    #[test]
+    fn test_keys_with_values() {
+        let mut trie = Trie::default();
+        trie.insert_value("rust", "rust-lang.org");
+        trie.insert_value("ruby", "ruby-lang.org");
+        trie.insert_value("python", "python.org");
+        assert_eq!(
+            trie.keys_with_values("ru"),
+            vec![
+                (String::from("ruby"), &"ruby-lang.org"),
+                (String::from("rust"), &"rust-lang.org"),
+            ]
+        );
+        assert_eq!(trie.keys_with_values("").len(), 3);
+        assert!(trie.keys_with_values("java").is_empty());
+    }
+
+    #[test]
+    fn test_prefix_frequency() {
+        let mut trie = Trie::new();
+        for word in ["car", "car", "car", "cart", "cat", "dog"] {
+            trie.insert(word);
+        }
+        assert_eq!(trie.prefix_frequency("car"), 4);
+        assert_eq!(trie.count_words_with_prefix("car"), 2);
+        assert_eq!(trie.prefix_frequency("ca"), 5);
+        assert_eq!(trie.prefix_frequency(""), 6);
+        assert_eq!(trie.len(), 4);
+        assert_eq!(trie.prefix_frequency("x"), 0);
+        trie.remove("car");
+        assert_eq!(trie.prefix_frequency("ca"), 2);
+        trie.retain(|word| word != "cat");
+        assert_eq!(trie.prefix_frequency(""), 2);
+        trie.remove_prefix("cart");
+        assert_eq!(trie.prefix_frequency(""), 1);
+        let mut other = Trie::new();
+        other.insert("dog");
+        other.insert("dot");
+        other.insert("dot");
+        trie.merge(&other);
+        // "dog" was already stored, so only the new word's inserts count
+        assert_eq!(trie.prefix_frequency("do"), 3);
+    }
+
+    #[test]
+    fn test_distinct_len_and_total_inserts() {
+        let mut trie = Trie::new();
+        for word in ["a", "a", "A", "b"] {
+            trie.insert(word);
+        }
+        assert_eq!(trie.distinct_len(), 2);
+        assert_eq!(trie.total_inserts(), 4);
+        // Rejected input isn't an insert
+        trie.insert("--");
+        assert_eq!(trie.total_inserts(), 4);
+        trie.remove("a");
+        assert_eq!(trie.distinct_len(), 1);
+        assert_eq!(trie.total_inserts(), 1);
+        assert_eq!(Trie::new().total_inserts(), 0);
+        assert_invariants(&trie);
+    }
+
+    #[test]
+    fn test_from_sorted_matches_inserts() {
+        let sorted = ["a", "ab", "abc", "abd", "b", "ba", "ba", "car", "cart"];
+        let trie = Trie::from_sorted(&sorted);
+        let mut expected = Trie::new();
+        expected.insert_all(sorted);
+        assert_eq!(trie, expected);
+        assert_eq!(trie.len(), 8);
+        assert_eq!(trie.node_count(), expected.node_count());
+        assert_eq!(trie.count_words_with_prefix("ab"), 3);
+        assert_eq!(trie.prefix_frequency("b"), 3);
+        assert_eq!(trie.most_common("", 1), expected.most_common("", 1));
+        // Unsorted input, also after normalization, gives the same trie
+        let unsorted = ["cart", "B", "a-b-d", "ba", "car", "abc", "a", "ab", "ba"];
+        assert_eq!(Trie::from_sorted(&unsorted), expected);
+        assert!(Trie::from_sorted(&["", "!!"]).is_empty());
+        assert_invariants(&trie);
+    }
+
+    #[test]
+    fn test_contains_any_and_all() {
+        let trie: Trie = ["apple", "ball", "cat"].iter().collect();
+        assert!(trie.contains_any(["dog", "Ball", "egg"]));
+        assert!(!trie.contains_any(["dog", "egg"]));
+        assert!(trie.contains_all(vec![String::from("cat"), String::from("apple")]));
+        assert!(!trie.contains_all(["cat", "dog", "apple"]));
+        assert!(!trie.contains_any([] as [&str; 0]));
+        assert!(trie.contains_all([] as [&str; 0]));
+        // Both stop at the word that settles the answer, in the middle here
+        let mut checked = 0;
+        let words = ["dog", "ball", "apple", "egg"];
+        assert!(trie.contains_any(words.iter().inspect(|_| checked += 1)));
+        assert_eq!(checked, 2);
+        checked = 0;
+        assert!(!trie.contains_all(words.iter().skip(1).inspect(|_| checked += 1)));
+        assert_eq!(checked, 3);
+    }
+
+    #[test]
+    fn test_ends_with() {
+        let trie: Trie = [
+            "sing",
+            "singing",
+            "ring",
+            "rang",
+            "bring",
+            "in",
+            "ping-pong",
+        ]
+        .iter()
+        .collect();
+        assert_eq!(
+            trie.ends_with("ing"),
+            vec!["bring", "ring", "sing", "singing"]
+        );
+        assert_eq!(trie.ends_with("ING"), trie.ends_with("ing"));
+        assert_eq!(trie.ends_with("in"), vec!["in"]);
+        assert_eq!(trie.ends_with("g-pong"), vec!["pingpong"]);
+        assert!(trie.ends_with("xyz").is_empty());
+        assert_eq!(trie.ends_with("").len(), trie.len());
+    }
+
+    #[test]
+    fn test_memory_usage_scales_with_nodes() {
+        let mut trie = Trie::new();
+        let empty = trie.memory_usage();
+        assert_eq!(empty, size_of::<Trie>());
+        trie.insert("abcdefghij");
+        let one_chain = trie.memory_usage();
+        assert!(one_chain >= empty + 10 * size_of::<TrieNode<()>>());
+        trie.insert("klmnopqrst");
+        let two_chains = trie.memory_usage();
+        assert_eq!(trie.node_count(), 21);
+        assert!(two_chains >= one_chain + 10 * size_of::<TrieNode<()>>());
+        trie.clear();
+        assert_eq!(trie.memory_usage(), empty);
+    }
+
+    #[test]
+    fn test_split_off_suffixes() {
+        let words = ["car", "cart", "carbon", "cat", "dog"];
+        let mut trie: Trie = words.iter().collect();
+        let split = trie.split_off("Car");
+        assert_eq!(split.words(""), vec!["bon", "t"]);
+        assert_eq!(split.len(), 2);
+        assert_eq!(trie.words(""), vec!["car", "cat", "dog"]);
+        assert_eq!(trie.len(), 3);
+        assert_eq!(trie.count_words_with_prefix("ca"), 2);
+        // Prefixing the suffixes again gives back the original words
+        let mut union = trie.clone();
+        for suffix in &split {
+            union.insert(&format!("car{suffix}"));
+        }
+        assert_eq!(union, words.iter().collect());
+        assert!(trie.split_off("x").is_empty());
+        assert_invariants(&trie);
+        assert_invariants(&split);
+    }
+
+    #[test]
+    fn test_split_off_with_prefix() {
+        let words = ["car", "cart", "carbon", "cat", "dog"];
+        let mut trie: Trie = words.iter().collect();
+        let split = trie.split_off_with_prefix("car");
+        assert_eq!(split.words(""), vec!["car", "carbon", "cart"]);
+        assert_eq!(split.count_words_with_prefix("c"), 3);
+        assert_eq!(split.prefix_frequency(""), 3);
+        assert_eq!(trie.words(""), vec!["cat", "dog"]);
+        let mut union = trie.clone();
+        union.merge(&split);
+        assert_eq!(union, words.iter().collect());
+        let everything = union.split_off_with_prefix("");
+        assert!(union.is_empty());
+        assert_eq!(everything.len(), 5);
+        assert_invariants(&union);
+        assert_invariants(&everything);
+    }
+
+    #[test]
+    fn test_depth_of() {
+        let mut trie = Trie::new();
+        trie.insert("a-b-c");
+        trie.insert("Ünïcode");
+        assert_eq!(trie.depth_of("a-b-c"), Some(3));
+        assert_eq!(trie.depth_of("ABC"), Some(3));
+        // Counted in chars, not bytes
+        assert_eq!(trie.depth_of("ünïcode"), Some(7));
+        assert_eq!(trie.depth_of("ab"), None);
+        assert_eq!(trie.depth_of(""), None);
+        let mut short = Trie::new()
+            .with_max_word_length(Some(4))
+            .with_length_policy(LengthPolicy::Truncate);
+        short.insert("abcdefg");
+        assert_eq!(short.depth_of("abcdefg"), Some(4));
+    }
+
+    // Checks the set operations against `HashSet`s of the same words
+    fn check_set_operations(a: &[&str], b: &[&str]) {
+        use std::collections::HashSet;
+
+        let trie_a: Trie = a.iter().collect();
+        let trie_b: Trie = b.iter().collect();
+        let set_a: HashSet<String> = trie_a.words("").into_iter().collect();
+        let set_b: HashSet<String> = trie_b.words("").into_iter().collect();
+        let sorted = |set: HashSet<&String>| {
+            let mut words: Vec<String> = set.into_iter().cloned().collect();
+            words.sort();
+            words
+        };
+        let intersection = trie_a.intersection(&trie_b);
+        assert_eq!(
+            intersection.words(""),
+            sorted(set_a.intersection(&set_b).collect())
+        );
+        assert_eq!(intersection.len(), intersection.words("").len());
+        let difference = trie_a.difference(&trie_b);
+        assert_eq!(
+            difference.words(""),
+            sorted(set_a.difference(&set_b).collect())
+        );
+        assert_eq!(difference.len(), difference.words("").len());
+        assert_eq!(difference.count_words_with_prefix(""), difference.len());
+        assert_invariants(&intersection);
+        assert_invariants(&difference);
+    }
+
+    #[test]
+    fn test_intersection_and_difference() {
+        check_set_operations(&["apple", "ape", "ball"], &["ape", "ball", "cat"]);
+        check_set_operations(&["a", "ab", "abc", "abd"], &["ab", "abcd", "b"]);
+        check_set_operations(&["car", "cart", "carbon"], &[]);
+        check_set_operations(&[], &["car"]);
+        check_set_operations(&["same", "words"], &["same", "words"]);
+        check_set_operations(
+            &["the", "quick", "brown", "fox", "then", "thee", "quiet"],
+            &["the", "quack", "brow", "fox", "them", "quiet", "q"],
+        );
+    }
+
+    #[test]
+    fn test_set_operations_very_long_word() {
+        // Deep enough to overflow the stack with a recursive walk
+        let word = "ab".repeat(25_000);
+        let mut a = Trie::new();
+        a.insert(&word);
+        a.insert(&format!("{word}c"));
+        a.insert("abc");
+        let mut b = Trie::new();
+        b.insert(&word);
+        b.insert("abd");
+        let both = a.intersection(&b);
+        assert_eq!(both.words(""), vec![word.clone()]);
+        assert_eq!(both.node_count(), 50_001);
+        let only_a = a.difference(&b);
+        assert_eq!(
+            only_a.words(""),
+            vec![format!("{word}c"), String::from("abc")]
+        );
+        assert_eq!(only_a.prefix_frequency(""), 2);
+    }
+
+    #[test]
+    fn test_intersection_keeps_values_from_self() {
+        let mut a = Trie::<u32>::default();
+        a.insert_value("apple", 1);
+        a.insert_value("ape", 2);
+        let b: Trie = ["apple", "apex"].iter().collect();
+        let both = a.intersection(&b);
+        assert_eq!(both.get("apple"), Some(&1));
+        assert_eq!(both.get("ape"), None);
+        // Prefixes of matched words aren't left behind as empty nodes
+        assert_eq!(both.node_count(), 6);
+        assert_eq!(a.difference(&b).get("ape"), Some(&2));
+    }
+
+    #[test]
+    fn test_is_subset_of() {
+        let words: Trie = ["apple", "ape", "ball", "cat"].iter().collect();
+        let subset: Trie = ["ape", "Cat"].iter().collect();
+        assert!(subset.is_subset_of(&words));
+        assert!(!words.is_subset_of(&subset));
+        assert!(words.is_subset_of(&words.clone()));
+        assert!(Trie::new().is_subset_of(&words));
+        assert!(Trie::new().is_subset_of(&Trie::new()));
+        // A stored prefix of another trie's word isn't in it
+        let prefix: Trie = ["app"].iter().collect();
+        assert!(!prefix.is_subset_of(&words));
+        let mut extra = subset.clone();
+        extra.insert("dog");
+        assert!(!extra.is_subset_of(&words));
+        let mut values = Trie::<u32>::default();
+        values.insert_value("ball", 7);
+        assert!(values.is_subset_of(&words));
+        assert!(!words.is_subset_of(&values));
+    }
+
+    #[test]
+    fn test_next_chars() {
+        let trie: Trie = ["car", "cat", "can", "ca", "cold", "cod", "dog"]
+            .iter()
+            .collect();
+        assert_eq!(trie.next_chars("ca"), vec![('n', 1), ('r', 1), ('t', 1)]);
+        assert_eq!(trie.next_chars("C"), vec![('a', 4), ('o', 2)]);
+        assert_eq!(trie.next_chars(""), vec![('c', 6), ('d', 1)]);
+        assert!(trie.next_chars("cat").is_empty());
+        assert!(trie.next_chars("x").is_empty());
+    }
+
+    #[test]
+    fn test_truncate_depth() {
+        let words = ["cart", "carbon", "car", "cat", "dog", "do", "a", "cattle"];
+        let mut trie: Trie = words.iter().collect();
+        trie.insert("cart");
+        trie.truncate_depth(3);
+        assert_eq!(trie.words(""), vec!["a", "car", "cat", "do", "dog"]);
+        assert_eq!(trie.len(), 5);
+        assert!(trie.iter().all(|word| word.chars().count() <= 3));
+        assert_eq!(trie.count_words_with_prefix("ca"), 2);
+        // "car" absorbs itself, "carbon" and both inserts of "cart"
+        assert_eq!(trie.most_common("car", 1), vec![(String::from("car"), 4)]);
+        assert_eq!(trie.prefix_frequency(""), 9);
+        let before = trie.clone();
+        trie.truncate_depth(10);
+        assert_eq!(trie, before);
+        trie.truncate_depth(0);
+        assert!(trie.is_empty());
+        assert_eq!(trie.node_count(), 1);
+        assert_invariants(&trie);
+    }
+
+    #[test]
+    fn test_truncate_depth_very_long_word() {
+        // Deep enough to overflow the stack with a recursive walk
+        let word = "ab".repeat(25_000);
+        let mut trie: Trie = [word.as_str(), "abc"].iter().collect();
+        trie.truncate_depth(usize::MAX);
+        assert_eq!(trie.words(""), vec![word.clone(), String::from("abc")]);
+        trie.truncate_depth(40_000);
+        assert_eq!(
+            trie.words(""),
+            vec![word[..40_000].to_string(), String::from("abc")]
+        );
+        assert_eq!(trie.len(), 2);
+        assert_eq!(trie.height(), 40_000);
+    }
+
+    #[test]
+    fn test_truncate_depth_drops_deeper_spellings() {
+        let mut trie = Trie::preserving_case();
+        trie.insert("NASA");
+        trie.insert("Nas");
+        trie.insert("Bread");
+        trie.truncate_depth(3);
+        assert_eq!(trie.words(""), vec!["bre", "Nas"]);
+        assert_invariants(&trie);
+    }
+
+    #[test]
+    fn test_to_set_and_to_vec() {
+        let inserts = ["delta", "alpha", "Charlie", "alpha", "bravo", "al-pha"];
+        let trie: Trie = inserts.iter().collect();
+        let mut expected = BTreeSet::new();
+        for word in ["delta", "alpha", "charlie", "bravo"] {
+            expected.insert(String::from(word));
+        }
+        assert_eq!(trie.to_set(), expected);
+        assert_eq!(trie.to_vec(), expected.into_iter().collect::<Vec<_>>());
+        assert!(Trie::new().to_set().is_empty());
+    }
+
+    #[test]
+    fn test_words_with_depth() {
+        let mut trie = Trie::preserving_case();
+        for word in ["Car", "cart", "carbon", "cat", "dog"] {
+            trie.insert(word);
+        }
+        assert_eq!(
+            trie.words_with_depth("CAR"),
+            vec![
+                (String::from("Car"), 3),
+                (String::from("carbon"), 6),
+                (String::from("cart"), 4),
+            ]
+        );
+        let all = trie.words_with_depth("");
+        assert_eq!(
+            all.iter().map(|(word, _)| word.clone()).collect::<Vec<_>>(),
+            trie.all_words()
+        );
+        assert!(all.iter().all(|(word, depth)| word.len() == *depth));
+        assert!(trie.words_with_depth("x").is_empty());
+    }
+
+    #[test]
+    fn test_walk() {
+        let trie: Trie = ["car", "cart", "cat", "do"].iter().collect();
+        let mut visited = Vec::new();
+        trie.walk(|prefix, is_end_of_word| visited.push((prefix.to_string(), is_end_of_word)));
+        assert_eq!(visited.len(), trie.node_count());
+        let prefixes: Vec<&str> = visited.iter().map(|(prefix, _)| prefix.as_str()).collect();
+        assert_eq!(
+            prefixes,
+            vec!["", "c", "ca", "car", "cart", "cat", "d", "do"]
+        );
+        let ends: Vec<String> = visited
+            .into_iter()
+            .filter(|(_, is_end_of_word)| *is_end_of_word)
+            .map(|(prefix, _)| prefix)
+            .collect();
+        assert_eq!(ends, trie.all_words());
+        let mut count = 0;
+        Trie::new().walk(|prefix, is_end_of_word| {
+            assert!(prefix.is_empty() && !is_end_of_word);
+            count += 1;
+        });
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn test_is_prefix_and_contains() {
+        let trie: Trie = ["app", "apple", "ball"].iter().collect();
+        // (input, is_prefix, contains)
+        let cases = [
+            ("ap", true, false),      // prefix only
+            ("ball", true, true),     // a word nothing extends
+            ("app", true, true),      // a word and a prefix of another
+            ("apples", false, false), // neither
+            ("c", false, false),
+            ("APP", true, true),
+            ("", true, false),
+        ];
+        for (input, is_prefix, contains) in cases {
+            assert_eq!(trie.is_prefix(input), is_prefix, "{input}");
+            assert_eq!(trie.contains(input), contains, "{input}");
+        }
+        assert!(!Trie::new().is_prefix(""));
+    }
+
+    #[test]
+    fn test_replace() {
+        let mut trie: Trie = ["teh", "cat", "the"].iter().collect();
+        // `old` missing: `new` isn't inserted
+        assert!(!trie.replace("dgo", "dog"));
+        assert!(!trie.contains("dog"));
+        // A real rename
+        assert!(trie.replace("cat", "Cats"));
+        assert_eq!(trie.words(""), vec!["cats", "teh", "the"]);
+        // Into a word that is already there
+        assert!(trie.replace("teh", "the"));
+        assert_eq!(trie.words(""), vec!["cats", "the"]);
+        assert_eq!(trie.most_common("the", 1), vec![(String::from("the"), 2)]);
+        // The same word after normalization
+        assert!(trie.replace("CATS", "c-a-t-s"));
+        assert!(!trie.replace("dog", "DOG"));
+        // Nothing to rename to
+        assert!(!trie.replace("cats", "!!"));
+        assert_eq!(trie.len(), 2);
+        assert_eq!(trie.count_words_with_prefix(""), 2);
+        assert_invariants(&trie);
+    }
+
+    #[test]
+    fn test_child_letters() {
+        let trie: Trie = ["apple", "apply", "ape", "bat"].iter().collect();
+        assert_eq!(trie.child_letters("ap"), vec!['e', 'p']);
+        assert_eq!(trie.child_letters("AP"), vec!['e', 'p']);
+        assert_eq!(trie.child_letters(""), vec!['a', 'b']);
+        assert_eq!(trie.child_letters("appl"), vec!['e', 'y']);
+        assert!(trie.child_letters("apple").is_empty());
+        assert!(trie.child_letters("x").is_empty());
+    }
+
+    #[test]
+    fn test_words_with_punctuation_in_prefix() {
+        let trie: Trie = ["apple", "apply", "ape"].iter().collect();
+        assert_eq!(trie.words("a-p"), vec!["ape", "apple", "apply"]);
+        assert_eq!(trie.words("AP.P"), vec!["apple", "apply"]);
+        assert_eq!(trie.suggest("a p p", 1), vec!["apple"]);
+        assert_eq!(trie.try_words("Ap'"), Ok(trie.words("ap")));
+        assert_eq!(trie.keys_with_values("APPL")[0].0, "apple");
+        assert_eq!(trie.iter_prefix("a-p-e").collect::<Vec<_>>(), vec!["ape"]);
+        assert_eq!(
+            trie.most_common("APP-", 1),
+            vec![(String::from("apple"), 1)]
+        );
+    }
+
+    #[test]
+    fn test_into_sorted_vec() {
+        let mut trie = Trie::preserving_case();
+        for word in ["banana", "Apple", "band", "ape", "a", "bandana"] {
+            trie.insert(word);
+        }
+        let expected = trie.all_words();
+        assert_eq!(trie.into_sorted_vec(), expected);
+        let long: Trie = ["x".repeat(50_000)].iter().collect();
+        assert_eq!(long.into_sorted_vec()[0].len(), 50_000);
+        assert!(Trie::new().into_sorted_vec().is_empty());
+    }
+
+    #[test]
+    fn test_words_in_range() {
+        let words = [
+            "a", "ab", "abc", "abd", "b", "ba", "banana", "band", "bandana", "c", "cherry",
+        ];
+        let trie: Trie = words.iter().collect();
+        let bounds = [
+            "", "a", "ab", "abc", "abcd", "b", "bana", "band", "bz", "c", "z",
+        ];
+        for start in bounds {
+            for end in bounds {
+                let expected: Vec<String> = trie
+                    .all_words()
+                    .into_iter()
+                    .filter(|word| word.as_str() >= start && word.as_str() < end)
+                    .collect();
+                assert_eq!(trie.words_in_range(start, end), expected, "{start}..{end}");
+            }
+        }
+        assert_eq!(
+            trie.words_in_range("B", "B-Z"),
+            vec!["b", "ba", "banana", "band", "bandana"]
+        );
+        assert!(trie.words_in_range("c", "b").is_empty());
+    }
+
+    #[test]
+    fn test_shrink_to_fit() {
+        let mut trie = Trie::builder().preserve_case(true).build_map::<usize>();
+        for (i, word) in ["Apple", "ape", "apply", "ball", "BAND", "x"]
+            .iter()
+            .enumerate()
+        {
+            trie.insert_value(word, i);
+        }
+        for i in 0..200 {
+            trie.insert_value(&format!("churn{i}"), i);
+        }
+        for i in 0..200 {
+            trie.remove(&format!("churn{i}"));
+        }
+        trie.insert_value("apple", 9);
+        let before = trie.clone();
+        let memory = trie.memory_usage();
+        trie.shrink_to_fit();
+        assert_eq!(trie, before);
+        assert_eq!(trie.words(""), before.words(""));
+        assert_eq!(trie.keys_with_values("a"), before.keys_with_values("a"));
+        assert_eq!(trie.most_common("", 2), before.most_common("", 2));
+        assert_eq!(trie.prefix_frequency("ap"), 4);
+        assert_eq!(trie.count_words_with_prefix("b"), 2);
+        assert_eq!(trie.node_count(), before.node_count());
+        assert!(trie.memory_usage() < memory);
+        let mut empty = Trie::new();
+        empty.shrink_to_fit();
+        assert!(empty.is_empty());
+        assert_invariants(&trie);
+    }
+}
@@ -0,0 +1,283 @@
+use alloc::{collections::VecDeque, string::String, vec, vec::Vec};
+
+use crate::Trie;
+
+/// Finds every stored word occurring in a text, in one pass over the text.
+///
+/// Created by [`Trie::matcher`]. This is an Aho-Corasick automaton: each node
+/// of the trie gets a failure link to the node for the longest suffix of its
+/// path that is also a path in the trie, so after a mismatch the scan carries
+/// on from there instead of starting over. Scanning costs time linear in the
+/// length of the text plus the number of matches, however many words there
+/// are.
+///
+/// The matcher is a snapshot: it doesn't borrow the trie, and doesn't see
+/// words inserted after it was built. Build it once and reuse it for many
+/// texts; [`Trie::find_all_matches_in_text`] builds one for a single text.
+#[derive(Debug, Clone)]
+pub struct TextMatcher {
+    states: Vec<State>,
+    // Empty, only kept to normalize text the same way as the trie
+    config: Trie,
+}
+
+#[derive(Debug, Clone)]
+struct State {
+    // Sorted by char, pointing into `states`
+    children: Vec<(char, usize)>,
+    parent: usize,
+    letter: char,
+    depth: usize,
+    is_word: bool,
+    original: Option<String>,
+    fail: usize,
+    // The nearest state along the failure links that ends a word
+    output: Option<usize>,
+}
+
+impl State {
+    fn child(&self, char_code: char) -> Option<usize> {
+        let i = self
+            .children
+            .binary_search_by_key(&char_code, |(key, _)| *key)
+            .ok()?;
+        Some(self.children[i].1)
+    }
+}
+
+impl<V> Trie<V> {
+    /// Builds a [`TextMatcher`] for the stored words, in time linear in the
+    /// number of nodes.
+    pub fn matcher(&self) -> TextMatcher {
+        let mut states = vec![State {
+            children: Vec::new(),
+            parent: 0,
+            letter: '\0',
+            depth: 0,
+            is_word: false,
+            original: None,
+            fail: 0,
+            output: None,
+        }];
+        // Numbering the states breadth first puts every state after all the
+        // shallower ones its failure link can point to
+        let mut queue = VecDeque::from([(&self.root, 0)]);
+        while let Some((node, id)) = queue.pop_front() {
+            for (char_code, child) in node.children.iter() {
+                let child_id = states.len();
+                states.push(State {
+                    children: Vec::new(),
+                    parent: id,
+                    letter: *char_code,
+                    depth: states[id].depth + 1,
+                    is_word: child.is_end_of_word(),
+                    original: child.original.clone(),
+                    fail: 0,
+                    output: None,
+                });
+                states[id].children.push((*char_code, child_id));
+                queue.push_back((child, child_id));
+            }
+        }
+        for id in 1..states.len() {
+            let State { parent, letter, .. } = states[id];
+            let fail = if parent == 0 {
+                0
+            } else {
+                TextMatcher::step(&states, states[parent].fail, letter)
+            };
+            states[id].fail = fail;
+            states[id].output = if states[fail].is_word {
+                Some(fail)
+            } else {
+                states[fail].output
+            };
+        }
+        TextMatcher {
+            states,
+            config: self.empty_like(),
+        }
+    }
+
+    /// Returns every occurrence of a stored word in `text`, as the byte range
+    /// it covers and the word found. See [`TextMatcher::find_all`].
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let trie: Trie = ["he", "she", "hers"].iter().collect();
+    /// assert_eq!(
+    ///     trie.find_all_matches_in_text("ushers"),
+    ///     vec![
+    ///         (1, 4, String::from("she")),
+    ///         (2, 4, String::from("he")),
+    ///         (2, 6, String::from("hers")),
+    ///     ]
+    /// );
+    /// ```
+    pub fn find_all_matches_in_text(&self, text: &str) -> Vec<(usize, usize, String)> {
+        self.matcher().find_all(text)
+    }
+}
+
+impl TextMatcher {
+    /// Returns every occurrence of a stored word in `text`, as
+    /// `(start, end, word)` with `text[start..end]` the matching text.
+    ///
+    /// Matches may overlap, and a word is found inside longer words too.
+    /// They are listed by where they end, and for the same end the longer
+    /// first. Text is normalized one character at a time like the trie's
+    /// input, so `"She"` matches `"she"`, but characters the trie wouldn't
+    /// store, such as spaces and punctuation, separate words: a match never
+    /// spans them, whatever the [`NonAlphaPolicy`](crate::NonAlphaPolicy).
+    pub fn find_all(&self, text: &str) -> Vec<(usize, usize, String)> {
+        let mut matches = Vec::new();
+        let mut state = 0;
+        let mut keys = Vec::new();
+        // Where in `text` each key since the last separator came from
+        let mut starts = Vec::new();
+        for (i, char_code) in text.char_indices() {
+            keys.clear();
+            if !self.char_keys(char_code, &mut keys) {
+                state = 0;
+                starts.clear();
+                continue;
+            }
+            let end = i + char_code.len_utf8();
+            for key in &keys {
+                starts.push(i);
+                state = Self::step(&self.states, state, *key);
+                let mut found = Some(state).filter(|state| self.states[*state].is_word);
+                found = found.or(self.states[state].output);
+                while let Some(word) = found {
+                    let start = starts[starts.len() - self.states[word].depth];
+                    matches.push((start, end, self.word(word)));
+                    found = self.states[word].output;
+                }
+            }
+        }
+        matches
+    }
+
+    // The state reached from `state` by `char_code`, following failure links
+    // until some state has that child, or the root
+    fn step(states: &[State], mut state: usize, char_code: char) -> usize {
+        loop {
+            if let Some(child) = states[state].child(char_code) {
+                return child;
+            }
+            if state == 0 {
+                return 0;
+            }
+            state = states[state].fail;
+        }
+    }
+
+    fn char_keys(&self, char_code: char, keys: &mut Vec<char>) -> bool {
+        #[cfg(feature = "fold-diacritics")]
+        if self.config.fold_diacritics && !char_code.is_ascii() {
+            // Folding works on whole strings, so take the long way round
+            let mut utf8 = [0; 4];
+            keys.extend(
+                self.config
+                    .normalize(char_code.encode_utf8(&mut utf8))
+                    .unwrap_or_default(),
+            );
+            return !keys.is_empty();
+        }
+        self.config.push_keys(char_code, keys)
+    }
+
+    // Spells the word ending at `state` by walking back up to the root
+    fn word(&self, state: usize) -> String {
+        if let Some(original) = &self.states[state].original {
+            return original.clone();
+        }
+        let mut letters = Vec::with_capacity(self.states[state].depth);
+        let mut state = state;
+        while state != 0 {
+            letters.push(self.states[state].letter);
+            state = self.states[state].parent;
+        }
+        letters.into_iter().rev().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::String, vec, vec::Vec};
+
+    use crate::Trie;
+
+    // Every occurrence, by trying each word at each position
+    fn brute_force(trie: &Trie, text: &str) -> Vec<(usize, usize, String)> {
+        let mut matches = Vec::new();
+        for (start, _) in text.char_indices() {
+            for word in trie.iter() {
+                if text[start..].starts_with(word.as_str()) {
+                    matches.push((start, start + word.len(), word));
+                }
+            }
+        }
+        matches.sort_by_key(|(start, end, _)| (*end, *start));
+        matches
+    }
+
+    #[test]
+    fn test_overlapping_keywords() {
+        let trie: Trie = ["he", "she", "his", "hers", "her", "cat", "scat", "a"]
+            .iter()
+            .collect();
+        let text = "she said his cat scattered; hers, too, he says.";
+        let matches = trie.find_all_matches_in_text(text);
+        for (start, end, word) in &matches {
+            assert_eq!(&text[*start..*end], word);
+        }
+        assert_eq!(matches, brute_force(&trie, text));
+        // "a" is found inside other words, and "cat" inside "scattered"
+        assert!(matches.contains(&(18, 21, String::from("cat"))));
+        assert!(matches.contains(&(17, 21, String::from("scat"))));
+        assert_eq!(matches.iter().filter(|(_, _, word)| word == "a").count(), 4);
+    }
+
+    #[test]
+    fn test_matches_dont_span_separators() {
+        let trie: Trie = ["the", "hec", "cat"].iter().collect();
+        let matches = trie.find_all_matches_in_text("The cat, thecat");
+        assert_eq!(
+            matches,
+            vec![
+                (0, 3, String::from("the")),
+                (4, 7, String::from("cat")),
+                (9, 12, String::from("the")),
+                (10, 13, String::from("hec")),
+                (12, 15, String::from("cat")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_spans_are_byte_offsets() {
+        let mut trie = Trie::preserving_case();
+        trie.insert("Café");
+        trie.insert("fé");
+        let text = "Un CAFÉ, s'il vous plaît";
+        let matches = trie.matcher().find_all(text);
+        assert_eq!(
+            matches,
+            vec![(3, 8, String::from("Café")), (5, 8, String::from("fé"))]
+        );
+        assert_eq!(&text[3..8], "CAFÉ");
+        assert!(Trie::new().find_all_matches_in_text(text).is_empty());
+        assert!(trie.find_all_matches_in_text("").is_empty());
+    }
+
+    #[test]
+    fn test_matcher_is_reusable() {
+        let trie: Trie = ["ab", "bc", "abcd", "c", "aa"].iter().collect();
+        let matcher = trie.matcher();
+        for text in ["abcd", "xabcabcdx", "cccc", "ababab", "bcd abc", "aaaa"] {
+            assert_eq!(matcher.find_all(text), brute_force(&trie, text), "{text}");
+        }
+    }
+}
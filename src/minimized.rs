@@ -0,0 +1,228 @@
+use alloc::{collections::BTreeMap, string::String, vec, vec::Vec};
+
+use crate::Trie;
+
+/// A read-only set of words in which identical subtrees are stored once.
+///
+/// Created by [`Trie::minimize`]. A `Trie` gives every word its own chain
+/// of nodes after the point where it branches off, so the common endings of
+/// a word list (`-ing`, `-ed`, `-ation`, ...) are repeated under every stem.
+/// Minimizing merges subtrees that hold the same set of suffixes into one
+/// node, turning the tree into a directed acyclic word graph, which for
+/// inflected word lists needs a fraction of the nodes.
+///
+/// Queries are normalized like the trie it was built from. Values, counts
+/// and case-preserved spellings aren't kept: a shared node can't tell which
+/// word reached it, so words are reported normalized.
+///
+/// ```
+/// use trie::Trie;
+///
+/// let trie: Trie = ["walk", "walked", "talk", "talked"].iter().collect();
+/// let minimized = trie.minimize();
+/// assert!(minimized.contains("talked"));
+/// assert_eq!(minimized.words("wa"), vec!["walk", "walked"]);
+/// assert!(minimized.node_count() < trie.node_count());
+/// ```
+#[derive(Debug, Clone)]
+pub struct MinimizedTrie {
+    // Children always come before their parents, so the root is last
+    nodes: Vec<MinimizedNode>,
+    len: usize,
+    // Empty, only kept to normalize queries the same way
+    config: Trie,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct MinimizedNode {
+    is_end_of_word: bool,
+    // Sorted by char, pointing into `nodes`
+    children: Vec<(char, usize)>,
+}
+
+impl MinimizedNode {
+    fn child(&self, char_code: &char) -> Option<usize> {
+        let i = self
+            .children
+            .binary_search_by_key(char_code, |(key, _)| *key)
+            .ok()?;
+        Some(self.children[i].1)
+    }
+}
+
+impl<V> Trie<V> {
+    /// Builds a read-only copy of the words with identical subtrees merged.
+    /// See [`MinimizedTrie`].
+    ///
+    /// Subtrees are compared bottom up, so each node is visited once and
+    /// merged by looking up its letters and already merged children.
+    pub fn minimize(&self) -> MinimizedTrie {
+        let mut nodes = Vec::new();
+        let mut ids = BTreeMap::new();
+        // Ids of finished subtrees, waiting for their parent
+        let mut finished = Vec::new();
+        let mut stack = vec![(&self.root, false)];
+        while let Some((node, expanded)) = stack.pop() {
+            if !expanded {
+                stack.push((node, true));
+                stack.extend(node.children.values().rev().map(|child| (child, false)));
+                continue;
+            }
+            // Children were pushed in reverse, so they finished in order
+            let first = finished.len() - node.children.len();
+            let minimized = MinimizedNode {
+                is_end_of_word: node.is_end_of_word(),
+                children: node
+                    .children
+                    .keys()
+                    .copied()
+                    .zip(finished.drain(first..))
+                    .collect(),
+            };
+            let id = *ids.entry(minimized).or_insert_with_key(|minimized| {
+                nodes.push(minimized.clone());
+                nodes.len() - 1
+            });
+            finished.push(id);
+        }
+        MinimizedTrie {
+            nodes,
+            len: self.len,
+            config: self.empty_like(),
+        }
+    }
+}
+
+impl MinimizedTrie {
+    /// Returns `true` if `word` is stored. See [`Trie::contains`].
+    pub fn contains(&self, word: &str) -> bool {
+        self.config
+            .normalize_word(word)
+            .and_then(|keys| self.find_keys(&keys))
+            .is_some_and(|node| self.nodes[node].is_end_of_word)
+    }
+
+    /// Returns `true` if any stored word starts with `prefix`. See
+    /// [`Trie::starts_with`].
+    pub fn starts_with(&self, prefix: &str) -> bool {
+        self.find_prefix(prefix).is_some() && !self.is_empty()
+    }
+
+    /// Returns every stored word starting with `prefix`, in alphabetical
+    /// order. See [`Trie::words`].
+    pub fn words(&self, prefix: &str) -> Vec<String> {
+        let mut words = Vec::new();
+        let Some(keys) = self.config.normalize(prefix) else {
+            return words;
+        };
+        let Some(start) = self.find_keys(&keys) else {
+            return words;
+        };
+        let mut path: String = keys.into_iter().collect();
+        let mut stack = vec![(start, path.len(), None)];
+        while let Some((node, parent_len, char_code)) = stack.pop() {
+            path.truncate(parent_len);
+            if let Some(char_code) = char_code {
+                path.push(char_code);
+            }
+            let node = &self.nodes[node];
+            if node.is_end_of_word {
+                words.push(path.clone());
+            }
+            let len = path.len();
+            stack.extend(
+                node.children
+                    .iter()
+                    .rev()
+                    .map(|(char_code, child)| (*child, len, Some(*char_code))),
+            );
+        }
+        words
+    }
+
+    /// Returns the number of words stored.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no words are stored.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of distinct nodes, including the root, for
+    /// comparison with [`Trie::node_count`].
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    fn find_prefix(&self, prefix: &str) -> Option<usize> {
+        self.find_keys(&self.config.normalize(prefix)?)
+    }
+
+    fn find_keys(&self, keys: &[char]) -> Option<usize> {
+        let mut current_node = self.nodes.len() - 1;
+        for char_code in keys {
+            current_node = self.nodes[current_node].child(char_code)?;
+        }
+        Some(current_node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{format, string::String, vec::Vec};
+
+    use crate::Trie;
+
+    fn inflected() -> Vec<String> {
+        let mut words = Vec::new();
+        for stem in ["walk", "talk", "jump", "play", "work", "call", "look"] {
+            for ending in ["", "s", "ed", "ing", "er", "ers"] {
+                words.push(format!("{stem}{ending}"));
+            }
+        }
+        words
+    }
+
+    #[test]
+    fn test_minimize_matches_trie() {
+        let words = inflected();
+        let trie: Trie = words.iter().collect();
+        let minimized = trie.minimize();
+        assert_eq!(minimized.len(), trie.len());
+        assert_eq!(minimized.words(""), trie.words(""));
+        for prefix in ["", "w", "walk", "walke", "tal", "x", "lookers"] {
+            assert_eq!(minimized.words(prefix), trie.words(prefix), "{prefix}");
+            assert_eq!(minimized.starts_with(prefix), trie.starts_with(prefix));
+        }
+        assert_eq!(minimized.words("TALK"), trie.words("talk"));
+        for word in ["walked", "Calling", "work", "wor", "jumpeds", ""] {
+            assert_eq!(minimized.contains(word), trie.contains(word), "{word}");
+        }
+    }
+
+    #[test]
+    fn test_minimize_shares_suffixes() {
+        let trie: Trie = inflected().iter().collect();
+        let minimized = trie.minimize();
+        // The endings are stored once, and so is every leaf
+        assert!(minimized.node_count() * 3 < trie.node_count());
+        let empty = Trie::new().minimize();
+        assert_eq!(empty.node_count(), 1);
+        assert!(empty.is_empty());
+        assert!(!empty.starts_with(""));
+        assert!(empty.words("").is_empty());
+    }
+
+    #[test]
+    fn test_minimize_normalizes_like_the_trie() {
+        let mut trie = Trie::builder().case_sensitive(true).build();
+        trie.insert("Walk");
+        trie.insert("walk");
+        let minimized = trie.minimize();
+        assert!(minimized.contains("Walk"));
+        assert!(!minimized.contains("WALK"));
+        assert_eq!(minimized.words(""), vec!["Walk", "walk"]);
+    }
+}
@@ -0,0 +1,390 @@
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::{Trie, TrieError};
+
+/// What a [`Trie`] does with characters outside its [`Alphabet`], such as
+/// punctuation.
+///
+/// The policy applies uniformly to every method that takes a word, prefix or
+/// query, so the same input is always treated the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NonAlphaPolicy {
+    /// Drop the character and keep the rest, so `"ape'"` is stored as `"ape"`.
+    #[default]
+    Skip,
+    /// Ignore the whole input: inserts do nothing, `contains` returns `false`
+    /// and prefix queries find no words.
+    Reject,
+    /// Behave like [`Reject`](NonAlphaPolicy::Reject), except that the `try_`
+    /// methods (such as [`Trie::try_insert`]) return
+    /// [`TrieError::NonAlphabetic`] instead of quietly doing nothing.
+    Error,
+}
+
+/// Which characters a [`Trie`] stores. Anything else is handled by the
+/// [`NonAlphaPolicy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Alphabet {
+    /// Letters and digits in any script, as decided by
+    /// [`char::is_alphanumeric`].
+    #[default]
+    Unicode,
+    /// Only `a` to `z` and `0` to `9`. Uppercase ASCII letters are lowercased
+    /// into range unless the trie is case-sensitive.
+    AsciiLowercase,
+}
+
+impl Alphabet {
+    // Whether `input` can contribute keys at all, before lowercasing. Some
+    // non-ASCII letters lowercase into ASCII, such as the Kelvin sign to `k`,
+    // and mustn't sneak into the ASCII alphabet that way.
+    fn admits(self, input: char) -> bool {
+        match self {
+            Alphabet::Unicode => true,
+            Alphabet::AsciiLowercase => input.is_ascii(),
+        }
+    }
+
+    fn contains(self, char_code: char) -> bool {
+        match self {
+            Alphabet::Unicode => char_code.is_alphanumeric(),
+            Alphabet::AsciiLowercase => {
+                char_code.is_ascii_lowercase() || char_code.is_ascii_digit()
+            }
+        }
+    }
+}
+
+/// What a [`Trie`] does with words longer than its
+/// [`max_word_length`](Trie::max_word_length).
+///
+/// Like the limit itself, this only applies to whole words (inserting and
+/// looking them up), not to prefixes or other queries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LengthPolicy {
+    /// Refuse the word: inserts do nothing and return `false`, lookups find
+    /// nothing, and the `try_` methods return [`TrieError::WordTooLong`].
+    #[default]
+    Reject,
+    /// Keep only the first `max_word_length` characters. Case-preserving
+    /// tries report a truncated word in its normalized form.
+    Truncate,
+}
+
+impl<V> Trie<V> {
+    // Turns input into the keys stored along its path. `Ok(None)` means the
+    // policy says to silently ignore the input.
+    //
+    // Characters are lowercased one at a time, so a prefix always normalizes
+    // to a prefix of the words it starts: lowercasing whole strings would turn
+    // a final `Σ` into `ς` and the same letter further in into `σ`. A letter
+    // whose lowercase form is several characters keeps those in the alphabet
+    // and drops the rest, so `İ` becomes `i` without the combining dot it
+    // lowercases to. Policies judge the input character, never those pieces.
+    pub(crate) fn try_normalize(&self, text: &str) -> Result<Option<Vec<char>>, TrieError> {
+        #[cfg(feature = "fold-diacritics")]
+        let folded;
+        #[cfg(feature = "fold-diacritics")]
+        // ASCII has no combining marks to strip
+        let text = if self.fold_diacritics && !text.is_ascii() {
+            use unicode_normalization::{UnicodeNormalization, char::is_combining_mark};
+
+            folded = text
+                .nfd()
+                .filter(|char_code| !is_combining_mark(*char_code))
+                .nfc()
+                .collect::<String>();
+            &folded
+        } else {
+            text
+        };
+        let mut keys = Vec::with_capacity(text.len());
+        for char_code in text.chars() {
+            if self.push_keys(char_code, &mut keys) {
+                continue;
+            }
+            match self.non_alpha_policy {
+                NonAlphaPolicy::Skip => {}
+                NonAlphaPolicy::Reject => return Ok(None),
+                NonAlphaPolicy::Error => return Err(TrieError::NonAlphabetic(char_code)),
+            }
+        }
+        Ok(Some(keys))
+    }
+
+    // Appends the keys for a single character, lowercased unless the trie is
+    // case-sensitive, returning `false` if it has none in the alphabet. Input
+    // is assumed to be folded already.
+    pub(crate) fn push_keys(&self, char_code: char, keys: &mut Vec<char>) -> bool {
+        let before = keys.len();
+        if char_code.is_ascii() {
+            // ASCII lowercases to a single ASCII character, without the
+            // Unicode case tables
+            let key = if self.case_sensitive {
+                char_code
+            } else {
+                char_code.to_ascii_lowercase()
+            };
+            if self.alphabet.contains(key) {
+                keys.push(key);
+            }
+        } else if !self.alphabet.admits(char_code) {
+            // Left to the caller's policy
+        } else if self.case_sensitive {
+            keys.extend(Some(char_code).filter(|key| self.alphabet.contains(*key)));
+        } else {
+            keys.extend(
+                char_code
+                    .to_lowercase()
+                    .filter(|key| self.alphabet.contains(*key)),
+            );
+        }
+        keys.len() > before
+    }
+
+    // Same as `try_normalize`, for the infallible methods that treat any
+    // invalid input as absent
+    pub(crate) fn normalize(&self, text: &str) -> Option<Vec<char>> {
+        self.try_normalize(text).ok().flatten()
+    }
+
+    // Like `try_normalize`, for a whole word to store or look up. Input that
+    // normalizes to nothing is ignored rather than stored at the root, and
+    // the length limit applies.
+    pub(crate) fn try_normalize_word(&self, word: &str) -> Result<Option<Vec<char>>, TrieError> {
+        let Some(mut keys) = self.try_normalize(word)? else {
+            return Ok(None);
+        };
+        if let Some(max) = self.max_word_length
+            && keys.len() > max
+        {
+            match self.length_policy {
+                LengthPolicy::Reject => {
+                    return Err(TrieError::WordTooLong {
+                        len: keys.len(),
+                        max,
+                    });
+                }
+                LengthPolicy::Truncate => keys.truncate(max),
+            }
+        }
+        if keys.is_empty() {
+            return Err(TrieError::EmptyWord);
+        }
+        Ok(Some(keys))
+    }
+
+    // Infallible counterpart of `try_normalize_word`
+    pub(crate) fn normalize_word(&self, word: &str) -> Option<Vec<char>> {
+        self.try_normalize_word(word).ok().flatten()
+    }
+
+    // The spelling to remember for `word`, stored as `keys`. Only
+    // case-preserving tries keep one, and not for truncated words, whose
+    // spelling no longer matches what is stored.
+    pub(crate) fn original_spelling(&self, word: &str, keys: &[char]) -> Option<String> {
+        if !self.preserve_case {
+            return None;
+        }
+        let truncated = self.max_word_length == Some(keys.len())
+            && self
+                .normalize(word)
+                .is_some_and(|full| full.len() > keys.len());
+        (!truncated).then(|| word.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Alphabet, LengthPolicy, NonAlphaPolicy, Trie, TrieError};
+
+    #[test]
+    fn test_skip_policy_is_default() {
+        let mut trie = Trie::new();
+        assert_eq!(trie.non_alpha_policy(), NonAlphaPolicy::Skip);
+        trie.insert("ape'");
+        assert!(trie.contains("ape"));
+        assert!(trie.contains("a-p-e"));
+        assert!(trie.starts_with("a."));
+    }
+
+    #[test]
+    fn test_reject_policy() {
+        let mut trie = Trie::new().with_non_alpha_policy(NonAlphaPolicy::Reject);
+        trie.insert("ape'");
+        assert!(trie.is_empty());
+        trie.insert("apple");
+        assert!(trie.contains("apple"));
+        assert!(!trie.contains("apple!"));
+        assert!(trie.words("ap!").is_empty());
+        assert!(!trie.starts_with("a p"));
+        assert!(!trie.remove("apple?"));
+        assert_eq!(trie.try_insert("ape'"), Ok(false));
+        assert_eq!(trie.try_contains("apple!"), Ok(false));
+        assert_eq!(trie.len(), 1);
+    }
+
+    #[test]
+    fn test_error_policy() {
+        let mut trie = Trie::new().with_non_alpha_policy(NonAlphaPolicy::Error);
+        assert_eq!(trie.try_insert("ape'"), Err(TrieError::NonAlphabetic('\'')));
+        assert!(trie.is_empty());
+        assert_eq!(trie.try_insert("apple"), Ok(true));
+        assert_eq!(trie.try_contains("apple"), Ok(true));
+        assert_eq!(
+            trie.try_contains("app le"),
+            Err(TrieError::NonAlphabetic(' '))
+        );
+        assert_eq!(trie.try_words("ap"), Ok(vec![String::from("apple")]));
+        assert_eq!(trie.try_words("a-"), Err(TrieError::NonAlphabetic('-')));
+        // The infallible methods treat bad input like Reject does
+        trie.insert("ball!");
+        assert!(!trie.contains("apple!"));
+        assert_eq!(trie.len(), 1);
+    }
+
+    #[test]
+    fn test_empty_word_is_ignored() {
+        let mut trie = Trie::new();
+        assert!(!trie.insert(""));
+        assert!(!trie.insert("!!!"));
+        assert_eq!(trie.try_insert(" - "), Err(TrieError::EmptyWord));
+        assert!(trie.is_empty());
+        assert!(!trie.contains(""));
+        assert!(trie.words("").is_empty());
+        trie.insert("a");
+        assert!(!trie.contains(""));
+        assert_eq!(trie.words(""), vec!["a"]);
+        assert_eq!(trie.node_count(), 2);
+        let mut map = Trie::<u32>::default();
+        assert_eq!(map.insert_value("?", 1), None);
+        assert_eq!(map.get(""), None);
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn test_try_methods_report_each_error() {
+        let mut trie = Trie::builder()
+            .non_alpha(NonAlphaPolicy::Error)
+            .max_word_length(4)
+            .build_map::<u32>();
+        assert_eq!(trie.try_insert_value("", 1), Err(TrieError::EmptyWord));
+        assert_eq!(trie.try_contains(""), Err(TrieError::EmptyWord));
+        assert_eq!(
+            trie.try_insert_value("a b", 1),
+            Err(TrieError::NonAlphabetic(' '))
+        );
+        assert_eq!(
+            trie.try_contains("abcde"),
+            Err(TrieError::WordTooLong { len: 5, max: 4 })
+        );
+        assert_eq!(trie.try_insert_value("abcd", 1), Ok(None));
+        assert!(trie.try_words("").is_ok());
+        // Under Skip, input made only of skipped characters is empty
+        let mut skip = Trie::new();
+        assert_eq!(skip.try_insert("?!"), Err(TrieError::EmptyWord));
+        assert_eq!(skip.try_contains("--"), Err(TrieError::EmptyWord));
+        // Reject ignores the word without an error
+        let mut reject = Trie::new().with_non_alpha_policy(NonAlphaPolicy::Reject);
+        assert_eq!(reject.try_insert("?!"), Ok(false));
+        assert!(skip.is_empty() && reject.is_empty());
+        assert_eq!(
+            TrieError::EmptyWord.to_string(),
+            "word is empty once normalized"
+        );
+    }
+
+    #[test]
+    fn test_max_word_length_boundary() {
+        let mut trie = Trie::builder().max_word_length(5).build();
+        assert!(trie.insert("apple"));
+        assert!(trie.insert("a-p-p-l-y"));
+        assert!(!trie.insert("apples"));
+        assert_eq!(
+            trie.try_insert("apples"),
+            Err(TrieError::WordTooLong { len: 6, max: 5 })
+        );
+        assert_eq!(trie.words(""), vec!["apple", "apply"]);
+        assert!(!trie.contains("apples"));
+        assert_eq!(
+            trie.try_contains("apples"),
+            Err(TrieError::WordTooLong { len: 6, max: 5 })
+        );
+        // Prefix queries aren't limited
+        assert!(trie.words("applesauce").is_empty());
+        assert_eq!(trie.height(), 5);
+    }
+
+    #[test]
+    fn test_truncate_long_words() {
+        let mut trie = Trie::builder()
+            .max_word_length(3)
+            .length_policy(LengthPolicy::Truncate)
+            .preserve_case(true)
+            .build();
+        assert!(trie.insert("Cat"));
+        assert!(!trie.insert("Catalog"));
+        assert_eq!(trie.try_insert("Category"), Ok(false));
+        assert!(trie.contains("catapult"));
+        assert_eq!(trie.words(""), vec!["cat"]);
+        assert!(trie.insert("Dog"));
+        assert_eq!(trie.words("d"), vec!["Dog"]);
+        assert_eq!(trie.most_common("", 1), vec![(String::from("cat"), 3)]);
+    }
+
+    #[test]
+    fn test_no_length_limit_by_default() {
+        let mut trie = Trie::new();
+        assert_eq!(trie.max_word_length(), None);
+        assert_eq!(trie.length_policy(), LengthPolicy::Reject);
+        let word = "a".repeat(10_000);
+        assert_eq!(trie.try_insert(&word), Ok(true));
+        assert!(trie.contains(&word));
+    }
+
+    #[test]
+    fn test_lowercase_expansions() {
+        let mut trie = Trie::new().with_non_alpha_policy(NonAlphaPolicy::Error);
+        // `İ` lowercases to `i` and a combining dot, which isn't a letter
+        assert_eq!(trie.try_insert("İstanbul"), Ok(true));
+        assert!(trie.contains("istanbul"));
+        assert_eq!(trie.height(), 8);
+        // `ß` has no single-letter uppercase, but `ẞ` lowercases to it
+        assert_eq!(trie.try_insert("STRAẞE"), Ok(true));
+        assert!(trie.contains("straße"));
+        assert_eq!(trie.words("stra"), vec!["straße"]);
+        assert_eq!(trie.try_insert("Ǆemal"), Ok(true));
+        assert!(trie.contains("ǆemal"));
+        assert_eq!(trie.len(), 3);
+    }
+
+    #[test]
+    fn test_lowercase_prefixes_match_words() {
+        let mut trie = Trie::new();
+        trie.insert("ΟΔΟΣ");
+        trie.insert("ΟΔΟΣΟΣ");
+        // A final sigma stays `σ`, so the shorter word is a prefix of the other
+        assert_eq!(trie.count_words_with_prefix("ΟΔΟΣ"), 2);
+        assert!(trie.contains("οδοσ"));
+        assert_eq!(trie.height(), 6);
+    }
+
+    #[test]
+    fn test_ascii_alphabet_rejects_non_ascii_uppercase() {
+        let mut trie = Trie::builder()
+            .alphabet(Alphabet::AsciiLowercase)
+            .non_alpha(NonAlphaPolicy::Error)
+            .build();
+        // The Kelvin sign lowercases to an ASCII `k`
+        assert_eq!(
+            trie.try_insert("\u{212a}ilo"),
+            Err(TrieError::NonAlphabetic('\u{212a}'))
+        );
+        assert_eq!(trie.try_insert("İzmir"), Err(TrieError::NonAlphabetic('İ')));
+        assert_eq!(trie.try_insert("KILO"), Ok(true));
+        assert_eq!(trie.words(""), vec!["kilo"]);
+    }
+}
@@ -0,0 +1,412 @@
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, btree_map::Entry},
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+/// A compressed trie that stores runs of non-branching characters on a
+/// single edge.
+///
+/// Where [`Trie`](crate::Trie) allocates one node per character, a chain
+/// such as the shared tail of `"/static/img/logo.png"` and
+/// `"/static/img/icon.png"` collapses into one edge labeled with the whole
+/// run, so long, rarely branching words need far fewer nodes. Words are
+/// normalized the same way as a default `Trie`: lowercased, with anything
+/// other than letters and digits skipped.
+///
+/// ```
+/// use trie::{RadixTrie, Trie};
+///
+/// let words = ["interstellar", "internet", "interval"];
+/// let trie: Trie = words.iter().collect();
+/// let radix: RadixTrie = words.iter().collect();
+/// assert_eq!(radix.words("inter"), trie.words("inter"));
+/// assert!(radix.node_count() < trie.node_count());
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct RadixTrie {
+    root: RadixNode,
+    len: usize,
+}
+
+#[derive(Debug, Default)]
+struct RadixNode {
+    // Keyed by the first character of each edge's label, which is unique
+    // among siblings, so iteration is in alphabetical order
+    children: BTreeMap<char, RadixEdge>,
+    is_end_of_word: bool,
+}
+
+#[derive(Debug, Clone)]
+struct RadixEdge {
+    // Never empty
+    label: String,
+    node: Box<RadixNode>,
+}
+
+impl RadixTrie {
+    /// Creates an empty radix trie.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts a word, returning `true` if it wasn't stored before.
+    ///
+    /// Like [`Trie::insert`](crate::Trie::insert), input that normalizes to
+    /// the empty word is ignored.
+    pub fn insert(&mut self, word: &str) -> bool {
+        let key = normalize(word);
+        if key.is_empty() {
+            return false;
+        }
+        let mut current_node = &mut self.root;
+        let mut rest = key.as_str();
+        while let Some(first) = rest.chars().next() {
+            let edge = match current_node.children.entry(first) {
+                Entry::Occupied(slot) => slot.into_mut(),
+                Entry::Vacant(slot) => {
+                    // Nothing shares this suffix, so it becomes a single edge
+                    let leaf = RadixNode {
+                        children: BTreeMap::new(),
+                        is_end_of_word: true,
+                    };
+                    slot.insert(RadixEdge {
+                        label: rest.to_string(),
+                        node: Box::new(leaf),
+                    });
+                    self.len += 1;
+                    return true;
+                }
+            };
+            let common = common_prefix_len(&edge.label, rest);
+            if common < edge.label.len() {
+                // The word leaves this edge part way along, so split it in two
+                let tail = edge.label.split_off(common);
+                let tail_first = tail.chars().next().unwrap();
+                let lower = core::mem::take(&mut edge.node);
+                edge.node.children.insert(
+                    tail_first,
+                    RadixEdge {
+                        label: tail,
+                        node: lower,
+                    },
+                );
+            }
+            current_node = &mut edge.node;
+            rest = &rest[common..];
+        }
+        let is_new = !current_node.is_end_of_word;
+        current_node.is_end_of_word = true;
+        if is_new {
+            self.len += 1;
+        }
+        is_new
+    }
+
+    /// Returns `true` if `word` was inserted as a whole word.
+    pub fn contains(&self, word: &str) -> bool {
+        let key = normalize(word);
+        let mut current_node = &self.root;
+        let mut rest = key.as_str();
+        while let Some(first) = rest.chars().next() {
+            match current_node.children.get(&first) {
+                Some(edge) if rest.starts_with(&edge.label) => {
+                    current_node = &edge.node;
+                    rest = &rest[edge.label.len()..];
+                }
+                _ => return false,
+            }
+        }
+        current_node.is_end_of_word
+    }
+
+    /// Returns every stored word starting with `prefix`, in alphabetical order.
+    pub fn words(&self, prefix: &str) -> Vec<String> {
+        let key = normalize(prefix);
+        let mut current_node = &self.root;
+        let mut path = String::new();
+        let mut rest = key.as_str();
+        while let Some(first) = rest.chars().next() {
+            let Some(edge) = current_node.children.get(&first) else {
+                return Vec::new();
+            };
+            if rest.starts_with(&edge.label) {
+                rest = &rest[edge.label.len()..];
+            } else if edge.label.starts_with(rest) {
+                // The prefix ends part way along this edge
+                rest = "";
+            } else {
+                return Vec::new();
+            }
+            path.push_str(&edge.label);
+            current_node = &edge.node;
+        }
+
+        // Like `Words`, every node remembers how long its parent's path was,
+        // so one buffer is shared instead of copying the path at every edge
+        let mut words = Vec::new();
+        let mut stack = vec![(current_node, path.len(), "")];
+        while let Some((node, parent_len, label)) = stack.pop() {
+            path.truncate(parent_len);
+            path.push_str(label);
+            let len = path.len();
+            for edge in node.children.values().rev() {
+                stack.push((&edge.node, len, edge.label.as_str()));
+            }
+            if node.is_end_of_word {
+                words.push(path.clone());
+            }
+        }
+        words
+    }
+
+    /// Removes a word, returning `true` if it was present.
+    ///
+    /// Edges are merged back together once a split is no longer needed, so
+    /// the trie stays as compact as if the word had never been inserted.
+    pub fn remove(&mut self, word: &str) -> bool {
+        let key = normalize(word);
+        // The first character of every edge on the way to the word
+        let mut firsts = Vec::new();
+        let mut current_node = &self.root;
+        let mut rest = key.as_str();
+        while let Some(first) = rest.chars().next() {
+            match current_node.children.get(&first) {
+                Some(edge) if rest.starts_with(&edge.label) => {
+                    firsts.push(first);
+                    current_node = &edge.node;
+                    rest = &rest[edge.label.len()..];
+                }
+                _ => return false,
+            }
+        }
+        if !current_node.is_end_of_word {
+            return false;
+        }
+        // The root is never a word, so there is at least one edge. Only the
+        // word's node and its parent can be left without a reason to exist.
+        let (last, above) = firsts.split_last().unwrap();
+        let parent = node_at(&mut self.root, above);
+        let edge = parent.children.get_mut(last).unwrap();
+        edge.node.is_end_of_word = false;
+        if edge.node.children.is_empty() {
+            parent.children.remove(last);
+            if let Some((parent_first, above)) = above.split_last() {
+                let parent_edge = node_at(&mut self.root, above)
+                    .children
+                    .get_mut(parent_first)
+                    .unwrap();
+                fold_pass_through(parent_edge);
+            }
+        } else {
+            fold_pass_through(edge);
+        }
+        self.len -= 1;
+        true
+    }
+
+    /// Returns the number of distinct words stored.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no words are stored.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of allocated nodes, including the root.
+    ///
+    /// Compare with [`Trie::node_count`](crate::Trie::node_count) to measure
+    /// the savings from compression.
+    pub fn node_count(&self) -> usize {
+        let mut count = 0;
+        let mut stack = vec![&self.root];
+        while let Some(node) = stack.pop() {
+            count += 1;
+            stack.extend(node.children.values().map(|edge| &*edge.node));
+        }
+        count
+    }
+}
+
+// The node reached by following the edges starting with `firsts`
+fn node_at<'a>(mut node: &'a mut RadixNode, firsts: &[char]) -> &'a mut RadixNode {
+    for first in firsts {
+        node = &mut node.children.get_mut(first).unwrap().node;
+    }
+    node
+}
+
+// Merges the only edge below a node that is no longer a word into the edge
+// leading to it
+fn fold_pass_through(edge: &mut RadixEdge) {
+    if edge.node.is_end_of_word || edge.node.children.len() != 1 {
+        return;
+    }
+    let (_, only) = edge.node.children.pop_first().unwrap();
+    edge.label.push_str(&only.label);
+    edge.node = only.node;
+}
+
+// Cloned with an explicit stack, as for `TrieNode`, so a long chain of
+// nested words can't overflow it. Each copy is attached to its parent once
+// all of its own children are.
+impl Clone for RadixNode {
+    fn clone(&self) -> Self {
+        let copy = |node: &RadixNode| RadixNode {
+            children: BTreeMap::new(),
+            is_end_of_word: node.is_end_of_word,
+        };
+        let mut stack = vec![(self.children.iter(), copy(self), None)];
+        loop {
+            let (children, _, _) = stack.last_mut().unwrap();
+            if let Some((first, edge)) = children.next() {
+                let children = edge.node.children.iter();
+                stack.push((children, copy(&edge.node), Some((*first, &edge.label))));
+                continue;
+            }
+            let (_, node, edge) = stack.pop().unwrap();
+            match (stack.last_mut(), edge) {
+                (Some((_, parent, _)), Some((first, label))) => {
+                    let edge = RadixEdge {
+                        label: label.clone(),
+                        node: Box::new(node),
+                    };
+                    parent.children.insert(first, edge);
+                }
+                _ => return node,
+            }
+        }
+    }
+}
+
+// Torn down with an explicit stack for the same reason
+impl Drop for RadixNode {
+    fn drop(&mut self) {
+        let mut stack: Vec<RadixEdge> = core::mem::take(&mut self.children).into_values().collect();
+        while let Some(mut edge) = stack.pop() {
+            stack.extend(core::mem::take(&mut edge.node.children).into_values());
+        }
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for RadixTrie {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        let mut trie = RadixTrie::new();
+        for word in iter {
+            trie.insert(word.as_ref());
+        }
+        trie
+    }
+}
+
+// Lowercases and keeps letters and digits, like a default `Trie`
+fn normalize(word: &str) -> String {
+    word.chars()
+        .flat_map(char::to_lowercase)
+        .filter(|char_code| char_code.is_alphanumeric())
+        .collect()
+}
+
+// Length in bytes of the longest common prefix, always on a char boundary
+fn common_prefix_len(a: &str, b: &str) -> usize {
+    a.char_indices()
+        .zip(b.chars())
+        .find(|((_, a_char), b_char)| a_char != b_char)
+        .map_or(a.len().min(b.len()), |((i, _), _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RadixTrie;
+    use crate::Trie;
+
+    #[test]
+    fn test_insert_contains_words() {
+        let mut trie = RadixTrie::new();
+        assert!(trie.insert("romane"));
+        assert!(trie.insert("romanus"));
+        assert!(trie.insert("romulus"));
+        assert!(trie.insert("rubens"));
+        assert!(trie.insert("ruber"));
+        assert!(trie.insert("Rom"));
+        assert!(!trie.insert("rom"));
+        assert_eq!(trie.len(), 6);
+        assert!(trie.contains("romane"));
+        assert!(trie.contains("rom"));
+        assert!(!trie.contains("ro"));
+        assert!(!trie.contains("romanes"));
+        assert_eq!(
+            trie.words("rom"),
+            vec!["rom", "romane", "romanus", "romulus"]
+        );
+        assert_eq!(trie.words("ru"), vec!["rubens", "ruber"]);
+        assert_eq!(trie.words("roma"), vec!["romane", "romanus"]);
+        assert!(trie.words("rx").is_empty());
+    }
+
+    #[test]
+    fn test_matches_trie() {
+        let words = ["a", "ab", "abc", "b", "café", "cafe", "route66", "x", "!!"];
+        let trie: Trie = words.iter().collect();
+        let radix: RadixTrie = words.iter().collect();
+        assert_eq!(radix.words(""), trie.words(""));
+        assert_eq!(radix.words("caf"), trie.words("caf"));
+        assert_eq!(radix.len(), trie.len());
+        assert_eq!(radix.contains(""), trie.contains(""));
+    }
+
+    #[test]
+    fn test_remove_merges_edges() {
+        let mut trie: RadixTrie = ["test", "team", "toast"].iter().collect();
+        // root, "t", "e", "st", "am", "oast"
+        assert_eq!(trie.node_count(), 6);
+        assert!(trie.remove("team"));
+        assert!(!trie.remove("team"));
+        assert!(!trie.remove("te"));
+        // "e" and "st" are merged back into "est"
+        assert_eq!(trie.node_count(), 4);
+        assert_eq!(trie.words(""), vec!["test", "toast"]);
+        assert!(trie.remove("test"));
+        assert!(trie.remove("toast"));
+        assert!(trie.is_empty());
+        assert_eq!(trie.node_count(), 1);
+    }
+
+    #[test]
+    fn test_nested_words() {
+        // Deep enough to overflow the stack with recursive removes, clones
+        // or drops: every word is a node of its own
+        let word = "a".repeat(3_000);
+        let mut trie = RadixTrie::new();
+        for len in 1..=word.len() {
+            trie.insert(&word[..len]);
+        }
+        let mut copy = trie.clone();
+        assert_eq!(copy.node_count(), 3_001);
+        assert!(copy.remove(&word));
+        assert!(copy.remove(&word[..1_500]));
+        assert!(!copy.contains(&word[..1_500]));
+        assert!(copy.contains(&word[..1_501]));
+        assert_eq!(copy.node_count(), 2_999);
+        assert_eq!(trie.words(&word).len(), 1);
+        assert_eq!(copy.words(&word[..2_998]).len(), 2);
+    }
+
+    #[test]
+    fn test_node_count_savings() {
+        let paths = [
+            "static/images/branding/logo.png",
+            "static/images/branding/icon.png",
+            "static/scripts/application.js",
+            "static/styles/application.css",
+        ];
+        let trie: Trie = paths.iter().collect();
+        let radix: RadixTrie = paths.iter().collect();
+        assert_eq!(radix.words(""), trie.words(""));
+        assert!(radix.node_count() * 5 < trie.node_count());
+    }
+}
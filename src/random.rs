@@ -0,0 +1,81 @@
+use alloc::string::String;
+
+use rand::Rng;
+
+use crate::Trie;
+
+impl<V> Trie<V> {
+    /// Returns a uniformly random stored word, or `None` if the trie is empty.
+    ///
+    /// Enabled by the `rand` feature. Every node knows how many words lie
+    /// below it, so a single walk from the root picks a word without
+    /// collecting them first: at each node `rng` decides between the word
+    /// ending there and each child, weighted by how many words they hold.
+    pub fn random_word<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<String> {
+        if self.is_empty() {
+            return None;
+        }
+        let mut current_node = &self.root;
+        let mut path = String::new();
+        // Index of the chosen word among those below `current_node`
+        let mut index = rng.random_range(0..current_node.word_count);
+        loop {
+            if current_node.is_end_of_word() {
+                if index == 0 {
+                    return Some(current_node.display_word(path));
+                }
+                index -= 1;
+            }
+            let (char_code, child) = current_node.children.iter().find(|(_, child)| {
+                if index < child.word_count {
+                    return true;
+                }
+                index -= child.word_count;
+                false
+            })?;
+            path.push(*char_code);
+            current_node = child;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use rand::{SeedableRng, rngs::SmallRng};
+
+    use crate::Trie;
+
+    #[test]
+    fn test_random_word_empty() {
+        let mut rng = SmallRng::seed_from_u64(1);
+        assert_eq!(Trie::new().random_word(&mut rng), None);
+    }
+
+    #[test]
+    fn test_random_word_is_uniform() {
+        // Uneven shapes: a deep chain, a wide fan-out and words on inner nodes
+        let words = [
+            "a", "ab", "abc", "abcd", "b", "ba", "bb", "bc", "bd", "zebra",
+        ];
+        let trie: Trie = words.iter().collect();
+        let mut rng = SmallRng::seed_from_u64(42);
+        let draws = 50_000;
+        let mut seen = HashMap::new();
+        for _ in 0..draws {
+            let word = trie.random_word(&mut rng).unwrap();
+            *seen.entry(word).or_insert(0_usize) += 1;
+        }
+        assert_eq!(seen.len(), words.len());
+        let expected = draws / words.len();
+        for word in words {
+            let count = seen[word];
+            // Within 10% of the expected share, far above the noise at this size
+            assert!(
+                count.abs_diff(expected) < expected / 10,
+                "{word} drawn {count} times, expected about {expected}"
+            );
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader},
+    path::Path,
+};
+
+use crate::Trie;
+
+impl Trie {
+    /// Creates a trie from the word list in the file at `path`.
+    ///
+    /// The file holds one word per line, in UTF-8; lines are trimmed and
+    /// blank ones skipped, as in
+    /// [`insert_from_reader`](Self::insert_from_reader). Errors opening or
+    /// reading the file, including invalid UTF-8, are returned as they are.
+    ///
+    /// ```no_run
+    /// use trie::Trie;
+    ///
+    /// let trie = Trie::from_file("/usr/share/dict/words")?;
+    /// println!("{} words", trie.len());
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut trie = Trie::new();
+        trie.insert_from_reader(BufReader::new(File::open(path)?))?;
+        Ok(trie)
+    }
+
+    /// Inserts every line of `reader` as a word, returning how many weren't
+    /// stored before.
+    ///
+    /// Lines are read one at a time into a single buffer, so a large word
+    /// list is never held in memory all at once. Each line is trimmed, which
+    /// also takes care of `\r\n` endings, and blank lines are skipped. A read
+    /// error stops the load, leaving the words read so far inserted.
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    ///
+    /// use trie::Trie;
+    ///
+    /// let mut trie = Trie::new();
+    /// let added = trie.insert_from_reader(Cursor::new("apple\nape\n")).unwrap();
+    /// assert_eq!(added, 2);
+    /// ```
+    pub fn insert_from_reader<R: BufRead>(&mut self, mut reader: R) -> io::Result<usize> {
+        let mut line = String::new();
+        let mut added = 0;
+        while reader.read_line(&mut line)? > 0 {
+            let word = line.trim();
+            if !word.is_empty() && self.insert(word) {
+                added += 1;
+            }
+            line.clear();
+        }
+        Ok(added)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, BufRead, BufReader, Cursor, Read};
+
+    use crate::Trie;
+
+    #[test]
+    fn test_insert_from_reader() {
+        let text = "apple\r\nape\n\n  ball  \r\n\r\napple\nlast";
+        let mut trie = Trie::new();
+        assert_eq!(trie.insert_from_reader(Cursor::new(text)).unwrap(), 4);
+        assert_eq!(trie.len(), 4);
+        assert!(trie.contains("apple"));
+        assert!(trie.contains("ball"));
+        assert!(trie.contains("last"));
+        assert_eq!(
+            trie.most_common("apple", 1),
+            vec![(String::from("apple"), 2)]
+        );
+        assert_eq!(trie.insert_from_reader(Cursor::new("")).unwrap(), 0);
+    }
+
+    // Fails after handing out its input once
+    struct Broken<'a>(&'a [u8]);
+
+    impl Read for Broken<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.0.is_empty() {
+                return Err(io::Error::other("disk on fire"));
+            }
+            let n = self.0.read(buf)?;
+            self.0 = &[];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_insert_from_reader_error() {
+        let mut trie = Trie::new();
+        let reader: Box<dyn BufRead> = Box::new(BufReader::new(Broken(b"one\ntwo\n")));
+        let error = trie.insert_from_reader(reader).unwrap_err();
+        assert_eq!(error.to_string(), "disk on fire");
+        assert_eq!(trie.words(""), vec!["one", "two"]);
+    }
+
+    #[test]
+    fn test_from_file() {
+        let path = std::env::temp_dir().join(format!("trie-words-{}.txt", std::process::id()));
+        std::fs::write(&path, "apple\r\n\nape\nball\n").unwrap();
+        let trie = Trie::from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(trie.unwrap().words(""), vec!["ape", "apple", "ball"]);
+        let missing = Trie::from_file(&path).unwrap_err();
+        assert_eq!(missing.kind(), io::ErrorKind::NotFound);
+    }
+}
@@ -0,0 +1,117 @@
+//! `Serialize`/`Deserialize` support, enabled by the `serde` feature.
+//!
+//! A trie is written as a map from each stored word to its value, in
+//! alphabetical order. A plain `Trie` therefore becomes a JSON object whose
+//! values are all `null`:
+//!
+//! ```text
+//! {"ape":null,"apple":null}
+//! ```
+//!
+//! Storing words rather than the node structure keeps the output compact and
+//! keeps nesting shallow, so long words don't hit a deserializer's recursion
+//! limit. Deserializing inserts every word again into a trie configured like
+//! [`Trie::new`], which rebuilds the same trie if it had that configuration.
+
+use alloc::string::String;
+use core::{fmt, marker::PhantomData};
+
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::{MapAccess, Visitor},
+    ser::SerializeMap,
+};
+
+use crate::{Trie, Words};
+
+impl<V: Serialize> Serialize for Trie<V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        let mut entries = Words::new(Some((&self.root, String::new())));
+        while let Some((word, value)) = entries.next_entry() {
+            map.serialize_entry(&word, value)?;
+        }
+        map.end()
+    }
+}
+
+/// Inserts every word of the map into a [`Trie::default`].
+///
+/// The configuration isn't part of the format, so it isn't restored: words
+/// saved from a case-sensitive, case-preserving or otherwise configured trie
+/// are normalized the default way, and a word the default alphabet leaves
+/// nothing of is dropped. Every word counts as inserted once. Insert the
+/// words into a trie from [`Trie::builder`] to keep a configuration.
+impl<'de, V: Deserialize<'de>> Deserialize<'de> for Trie<V> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(TrieVisitor(PhantomData))
+    }
+}
+
+struct TrieVisitor<V>(PhantomData<V>);
+
+impl<'de, V: Deserialize<'de>> Visitor<'de> for TrieVisitor<V> {
+    type Value = Trie<V>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a map of words to values")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Trie<V>, A::Error> {
+        let mut trie = Trie::default();
+        while let Some((word, value)) = map.next_entry::<String, V>()? {
+            trie.insert_value(&word, value);
+        }
+        Ok(trie)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Trie;
+
+    #[test]
+    fn test_json_round_trip() {
+        let trie: Trie = ["apple", "ape", "ball", "café"].iter().collect();
+        let json = serde_json::to_string(&trie).unwrap();
+        assert_eq!(json, r#"{"ape":null,"apple":null,"ball":null,"café":null}"#);
+
+        let restored: Trie = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.len(), trie.len());
+        assert_eq!(restored.words(""), trie.words(""));
+        assert!(restored.contains("apple"));
+        assert!(!restored.contains("app"));
+    }
+
+    #[test]
+    fn test_json_round_trip_with_values() {
+        let mut trie: Trie<u32> = Trie::default();
+        trie.insert_value("one", 1);
+        trie.insert_value("two", 2);
+        let json = serde_json::to_string(&trie).unwrap();
+        assert_eq!(json, r#"{"one":1,"two":2}"#);
+
+        let restored: Trie<u32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.get("one"), Some(&1));
+        assert_eq!(restored.get("two"), Some(&2));
+    }
+
+    #[test]
+    fn test_json_config_not_restored() {
+        let mut trie = Trie::builder().case_sensitive(true).build();
+        trie.insert("Nasa");
+        trie.insert("nasa");
+        let json = serde_json::to_string(&trie).unwrap();
+        let restored: Trie = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.words(""), vec!["nasa"]);
+    }
+
+    #[test]
+    fn test_json_long_word() {
+        let word = "a".repeat(1000);
+        let trie: Trie = [word.as_str()].iter().collect();
+        let json = serde_json::to_string(&trie).unwrap();
+        let restored: Trie = serde_json::from_str(&json).unwrap();
+        assert!(restored.contains(&word));
+    }
+}
@@ -0,0 +1,170 @@
+use alloc::{string::String, vec};
+
+use crate::Trie;
+
+/// Measurements of the shape of a [`Trie`], returned by [`Trie::stats`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrieStats {
+    /// Allocated nodes, including the root, as in [`Trie::node_count`].
+    pub node_count: usize,
+    /// Distinct words stored, as in [`Trie::len`].
+    pub word_count: usize,
+    /// Length of the longest path from the root, as in [`Trie::height`].
+    pub height: usize,
+    /// Links from a node to a child. Every node but the root has exactly one
+    /// parent, so this is `node_count - 1`.
+    pub edge_count: usize,
+    /// Nodes with at least one child.
+    pub internal_node_count: usize,
+    /// Average number of children of an internal node, or `0.0` for an
+    /// empty trie. Long chains of single children pull it towards `1.0`.
+    pub average_branching: f64,
+}
+
+impl<V> Trie<V> {
+    /// Measures the trie in a single walk over its nodes. See [`TrieStats`].
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let trie: Trie = ["car", "cat"].iter().collect();
+    /// let stats = trie.stats();
+    /// assert_eq!(stats.node_count, 5);
+    /// assert_eq!(stats.average_branching, 4.0 / 3.0);
+    /// ```
+    pub fn stats(&self) -> TrieStats {
+        let mut node_count = 0;
+        let mut height = 0;
+        let mut edge_count = 0;
+        let mut internal_node_count = 0;
+        let mut stack = vec![(&self.root, 0)];
+        while let Some((node, depth)) = stack.pop() {
+            node_count += 1;
+            height = height.max(depth);
+            if !node.children.is_empty() {
+                internal_node_count += 1;
+                edge_count += node.children.len();
+            }
+            stack.extend(node.children.values().map(|child| (child, depth + 1)));
+        }
+        let average_branching = if internal_node_count == 0 {
+            0.0
+        } else {
+            edge_count as f64 / internal_node_count as f64
+        };
+        TrieStats {
+            node_count,
+            word_count: self.len,
+            height,
+            edge_count,
+            internal_node_count,
+            average_branching,
+        }
+    }
+
+    /// Returns the number of nodes below `prefix`, counting the node for
+    /// `prefix` itself, or 0 if no stored word starts with it.
+    ///
+    /// `count_nodes_with_prefix("")` is [`node_count`](Self::node_count).
+    pub fn count_nodes_with_prefix(&self, prefix: &str) -> usize {
+        let Some(start) = self.find_prefix(prefix) else {
+            return 0;
+        };
+        let mut count = 0;
+        let mut stack = vec![start];
+        while let Some(node) = stack.pop() {
+            count += 1;
+            stack.extend(node.children.values());
+        }
+        count
+    }
+
+    /// Returns the non-empty prefix shared by the most stored words, with
+    /// the number of words sharing it, or `("", 0)` for an empty trie.
+    ///
+    /// Every word below a prefix is also below each shorter prefix of it, so
+    /// a longer prefix never beats the letter it starts with, and ties go to
+    /// the shortest, alphabetically smallest prefix: the answer is always the
+    /// busiest first letter, and only the root's children need looking at.
+    /// [`next_chars`](Self::next_chars) breaks a prefix down further. The
+    /// prefix is reported normalized.
+    ///
+    /// ```
+    /// use trie::Trie;
+    ///
+    /// let trie: Trie = ["car", "cat", "cone", "dog"].iter().collect();
+    /// assert_eq!(trie.busiest_prefix(), (String::from("c"), 3));
+    /// ```
+    pub fn busiest_prefix(&self) -> (String, usize) {
+        let mut busiest = None;
+        for (char_code, child) in self.root.children.iter() {
+            // Children come in letter order, so only strictly busier ones win
+            if busiest.is_none_or(|(_, count)| child.word_count > count) {
+                busiest = Some((*char_code, child.word_count));
+            }
+        }
+        busiest.map_or((String::new(), 0), |(char_code, count)| {
+            (String::from(char_code), count)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TrieStats;
+    use crate::Trie;
+
+    #[test]
+    fn test_stats() {
+        // root - c - a - r - t
+        //              \- t
+        //      \ d - o
+        let trie: Trie = ["car", "cart", "cat", "do"].iter().collect();
+        assert_eq!(
+            trie.stats(),
+            TrieStats {
+                node_count: 8,
+                word_count: 4,
+                height: 4,
+                edge_count: 7,
+                internal_node_count: 5,
+                average_branching: 1.4,
+            }
+        );
+        assert_eq!(trie.stats().node_count, trie.node_count());
+        assert_eq!(trie.stats().height, trie.height());
+    }
+
+    #[test]
+    fn test_stats_empty() {
+        let stats = Trie::new().stats();
+        assert_eq!(stats.node_count, 1);
+        assert_eq!(stats.word_count, 0);
+        assert_eq!(stats.edge_count, 0);
+        assert_eq!(stats.internal_node_count, 0);
+        assert_eq!(stats.average_branching, 0.0);
+    }
+
+    #[test]
+    fn test_count_nodes_with_prefix() {
+        let trie: Trie = ["car", "cart", "cat", "do"].iter().collect();
+        assert_eq!(trie.count_nodes_with_prefix(""), trie.node_count());
+        assert_eq!(trie.count_nodes_with_prefix("ca"), 4);
+        assert_eq!(trie.count_nodes_with_prefix("CART"), 1);
+        assert_eq!(trie.count_nodes_with_prefix("x"), 0);
+    }
+
+    #[test]
+    fn test_busiest_prefix() {
+        let trie: Trie = ["apple", "banana", "band", "bandit", "bar", "cat", "cot"]
+            .iter()
+            .collect();
+        assert_eq!(trie.busiest_prefix(), (String::from("b"), 4));
+        let tied: Trie = ["dog", "cat"].iter().collect();
+        assert_eq!(tied.busiest_prefix(), (String::from("c"), 1));
+        // A single word is shared by nothing longer than its first letter
+        let one: Trie = ["Zebra"].iter().collect();
+        assert_eq!(one.busiest_prefix(), (String::from("z"), 1));
+        assert_eq!(Trie::new().busiest_prefix(), (String::new(), 0));
+    }
+}